@@ -0,0 +1,42 @@
+extends Node
+## Adds and removes a PixyTerrain repeatedly and checks nothing piles up between cycles.
+## Attach to any node in a scene and run it; results go to the output panel.
+
+@export var cycles: int = 20
+
+func _ready() -> void:
+	var terrain := PixyTerrain.new()
+	add_child(terrain)
+	await get_tree().process_frame
+	terrain.regenerate()
+	await get_tree().process_frame
+	var chunk_count: int = terrain.get_chunk_keys().size()
+
+	# Warm-up cycle, so lazily created materials and nodes exist before the baseline
+	await _cycle(terrain)
+	var baseline := Performance.get_monitor(Performance.OBJECT_NODE_COUNT)
+
+	for i in cycles:
+		await _cycle(terrain)
+
+	var nodes := Performance.get_monitor(Performance.OBJECT_NODE_COUNT)
+	assert(nodes <= baseline, "node count grew from %d to %d" % [baseline, nodes])
+	assert(terrain.get_chunk_keys().size() == chunk_count, "chunks lost across cycles")
+
+	# dispose() is safe to call again on an already disposed terrain
+	remove_child(terrain)
+	terrain.dispose()
+	terrain.dispose()
+	assert(terrain.get_chunk_keys().is_empty())
+	assert(terrain.get_node_or_null("CollisionProxy") == null)
+	terrain.free()
+	print("dispose_test: %d add/remove cycles OK" % cycles)
+
+func _cycle(terrain: PixyTerrain) -> void:
+	# Leave runtime-only nodes behind for exit_tree to release
+	terrain.build_collision_proxy(8.0)
+	terrain.set_selection([Vector2i(0, 0)])
+	remove_child(terrain)
+	await get_tree().process_frame
+	add_child(terrain)
+	await get_tree().process_frame
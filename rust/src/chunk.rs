@@ -8,15 +8,16 @@ use std::collections::HashMap;
 use godot::classes::mesh::PrimitiveType;
 use godot::classes::surface_tool::CustomFormat;
 use godot::classes::{
-    CollisionShape3D, ConcavePolygonShape3D, IMeshInstance3D, MeshInstance3D, Noise,
+    ArrayMesh, CollisionShape3D, ConcavePolygonShape3D, IMeshInstance3D, MeshInstance3D, Noise,
     ShaderMaterial, StaticBody3D, SurfaceTool,
 };
 use godot::prelude::*;
 
 use crate::flower_planter::{FlowerConfig, PixyFlowerPlanter};
 use crate::grass_planter::{GrassConfig, PixyGrassPlanter};
+use crate::grid::{chunk_aabb, chunk_center_xz, vertex_position};
 use crate::marching_squares::{
-    self, validate_cell_watertight, CellContext, CellGeometry, MergeMode,
+    self, check_manifold, validate_cell_watertight, CellContext, CellGeometry, MergeMode,
 };
 use crate::shared_params::SharedTerrainParams;
 
@@ -26,6 +27,17 @@ use crate::shared_params::SharedTerrainParams;
 pub struct TerrainConfig {
     pub shared: SharedTerrainParams,
     pub extra_collision_layer: i32,
+    pub generate_collision: bool,
+    /// Optional XZ rectangle (min, max). When set, only chunks whose center lies inside get colliders.
+    pub collision_region: Option<(Vector2, Vector2)>,
+    /// Scale applied to noise heights (`sample * dimensions.y * noise_amplitude`).
+    pub noise_amplitude: f32,
+    /// Step height for terracing noise heights; 0 disables.
+    pub terrace_height: f32,
+    /// 0 = smooth ramps between terraces, 1 = hard steps.
+    pub terrace_sharpness: f32,
+    /// Minimum triangle area kept in cell geometry; repeated-vertex triangles always go.
+    pub degenerate_area_epsilon: f32,
 }
 
 impl Default for TerrainConfig {
@@ -33,6 +45,12 @@ impl Default for TerrainConfig {
         Self {
             shared: SharedTerrainParams::default(),
             extra_collision_layer: 9,
+            generate_collision: true,
+            collision_region: None,
+            noise_amplitude: 1.0,
+            terrace_height: 0.0,
+            terrace_sharpness: 0.5,
+            degenerate_area_epsilon: 0.0,
         }
     }
 }
@@ -90,6 +108,9 @@ pub struct PixyTerrainChunk {
 
     pub is_new_chunk: bool,
 
+    /// Runtime-only mesh LOD: 0 = full resolution, each level doubles the cell stride.
+    lod: u32,
+
     #[export]
     #[init(val = false)]
     pub skip_save_on_exit: bool,
@@ -118,6 +139,7 @@ impl PixyTerrainChunk {
             cell_geometry: HashMap::new(),
             higher_poly_floors: true,
             is_new_chunk: false,
+            lod: 0,
             skip_save_on_exit: false,
             terrain_config: TerrainConfig::default(),
             terrain_material: None,
@@ -266,6 +288,37 @@ impl PixyTerrainChunk {
         self.color_maps.grass_mask[(z * dim_x + x) as usize]
     }
 
+    /// Terrain-local bounds of this chunk: the XZ grid footprint and the Y range of its heightmap.
+    #[func]
+    pub fn get_chunk_bounds(&self) -> Aabb {
+        let (min_y, max_y) = self.height_range();
+        chunk_aabb(
+            self.chunk_coords,
+            self.terrain_config.shared.dimensions,
+            self.terrain_config.shared.cell_size,
+            min_y,
+            max_y,
+        )
+    }
+
+    /// Edge-use counts for the whole chunk mesh. Diagnostic only: edges along the chunk
+    /// perimeter are always reported as boundary edges.
+    #[func]
+    pub fn get_manifold_report(&self) -> VarDictionary {
+        let verts: Vec<Vector3> = self
+            .cell_geometry
+            .values()
+            .flat_map(|geo| geo.verts.iter().copied())
+            .collect();
+        let report = check_manifold(&verts);
+
+        let mut dict = VarDictionary::new();
+        dict.set("boundary_edges", report.boundary_edges as i64);
+        dict.set("manifold_edges", report.manifold_edges as i64);
+        dict.set("non_manifold_edges", report.non_manifold_edges as i64);
+        dict
+    }
+
     #[func]
     pub fn validate_mesh_gaps(&self) -> i32 {
         let cell_size = self.terrain_config.shared.cell_size;
@@ -352,6 +405,15 @@ impl PixyTerrainChunk {
         }
     }
 
+    /// Lowest and highest heightmap values, or (0, 0) for an empty map.
+    pub fn height_range(&self) -> (f32, f32) {
+        let mut heights = self.height_map.iter().flatten().copied();
+        let Some(first) = heights.next() else {
+            return (0.0, 0.0);
+        };
+        heights.fold((first, first), |(lo, hi), h| (lo.min(h), hi.max(h)))
+    }
+
     fn get_dimensions_xz(&self) -> (i32, i32) {
         (
             self.terrain_config.shared.dimensions.x,
@@ -634,12 +696,80 @@ impl PixyTerrainChunk {
                     let noise_x = (self.chunk_coords.x * (dim.x - 1)) + x as i32;
                     let noise_z = (self.chunk_coords.y * (dim.z - 1)) + z as i32;
                     let sample = noise.get_noise_2d(noise_x as f32, noise_z as f32);
-                    self.height_map[z][x] = sample * dim.y as f32;
+                    self.height_map[z][x] = noise_height(sample, dim.y, &self.terrain_config);
                 }
             }
         }
     }
 
+    /// Swap the terrain material on the current mesh without regenerating geometry.
+    pub fn set_terrain_material(&mut self, material: Option<Gd<ShaderMaterial>>) {
+        self.terrain_material = material.clone();
+        if let Some(mat) = material {
+            if self.base().get_mesh().is_some() {
+                self.base_mut()
+                    .set_surface_override_material(0, &mat.upcast::<godot::classes::Material>());
+            }
+        }
+    }
+
+    /// Overwrite the heightmap by sampling `height_at(world_x, world_z)` at every grid vertex.
+    /// Does not rebuild the mesh; call `regenerate_mesh` afterwards.
+    pub fn bake_heights(&mut self, height_at: impl FnMut(f32, f32) -> f32) {
+        let dim = self.get_terrain_dimensions();
+        let grid = bake_height_grid(self.chunk_coords, dim, self.get_cell_size(), height_at);
+        for (z, row) in grid.iter().enumerate() {
+            for (x, h) in row.iter().enumerate() {
+                self.set_height_at(x as i32, z as i32, *h);
+            }
+        }
+    }
+
+    /// Resample heights (bilinear) and color maps (nearest) onto a `new_x` x `new_z` vertex grid
+    /// covering the same footprint. The current config must still describe the old grid; push the
+    /// new config, `sync_to_packed` and `initialize_terrain` afterwards.
+    pub fn resample_grid(&mut self, new_x: usize, new_z: usize) {
+        let dim = self.get_terrain_dimensions();
+        let (old_x, old_z) = (dim.x as usize, dim.z as usize);
+        self.height_map = resample_heights(&self.height_map, new_x, new_z);
+
+        let maps = &self.color_maps;
+        let nearest = |values: &[Color]| resample_nearest(values, old_x, old_z, new_x, new_z);
+        self.color_maps = marching_squares::ColorMaps {
+            color_0: nearest(&maps.color_0),
+            color_1: nearest(&maps.color_1),
+            wall_color_0: nearest(&maps.wall_color_0),
+            wall_color_1: nearest(&maps.wall_color_1),
+            grass_mask: nearest(&maps.grass_mask),
+        };
+        self.cell_geometry.clear();
+    }
+
+    /// Current mesh LOD level (0 = full resolution).
+    pub fn lod(&self) -> u32 {
+        self.lod
+    }
+
+    /// Switch to mesh LOD `lod`, re-meshing only if the level changed. Returns whether it did.
+    pub fn set_lod(&mut self, lod: u32) -> bool {
+        if lod == self.lod {
+            return false;
+        }
+        self.lod = lod;
+        self.regenerate_mesh();
+        true
+    }
+
+    /// Build a full-resolution mesh of the current heights and colours without touching the
+    /// chunk: its LOD, displayed mesh, cell cache, grass and collider are left as they are.
+    /// Costs one uncached mesh build, so it is meant for one-off exports such as baking.
+    pub fn build_full_res_mesh(&self) -> Option<Gd<ArrayMesh>> {
+        let mut st = begin_surface_tool();
+        self.generate_lod_cells(&mut st, 1);
+        st.generate_normals();
+        st.commit()
+    }
+
     pub fn regenerate_mesh(&mut self) {
         let material = self.terrain_material.clone();
         self.regenerate_mesh_with_material(material);
@@ -655,13 +785,14 @@ impl PixyTerrainChunk {
             }
         }
 
-        let mut st = SurfaceTool::new_gd();
-        st.begin(PrimitiveType::TRIANGLES);
-        st.set_custom_format(0, CustomFormat::RGBA_FLOAT);
-        st.set_custom_format(1, CustomFormat::RGBA_FLOAT);
-        st.set_custom_format(2, CustomFormat::RGBA_FLOAT);
+        let mut st = begin_surface_tool();
 
-        self.generate_terrain_cells(&mut st);
+        let step = lod_step(self.lod, dim_x, dim_z);
+        if step > 1 {
+            self.generate_lod_cells(&mut st, step);
+        } else {
+            self.generate_terrain_cells(&mut st);
+        }
 
         st.generate_normals();
 
@@ -675,21 +806,13 @@ impl PixyTerrainChunk {
                     .set_surface_override_material(0, &mat.upcast::<godot::classes::Material>());
             }
 
-            // Remove any existing StaticBody3D children (cleanup for re-generation)
-            let children = self.base().get_children_ex().include_internal(true).done();
-            for i in (0..children.len()).rev() {
-                if let Some(child) = children.get(i) {
-                    if child.is_class("StaticBody3D") {
-                        let mut child = child;
-                        self.base_mut().remove_child(&child);
-                        child.queue_free();
-                    }
-                }
-            }
+            self.rebuild_collision();
+        }
 
-            // Create collision via Godot's built-in method, then configure it.
-            self.base_mut().create_trimesh_collision();
-            self.configure_collision();
+        // Coarse meshes cache no cell geometry; grass and flowers keep their full-res placement
+        if step > 1 {
+            self.sync_to_packed();
+            return;
         }
 
         // Regenerate grass after mesh geometry is built
@@ -710,32 +833,36 @@ impl PixyTerrainChunk {
         self.sync_to_packed();
     }
 
-    fn generate_terrain_cells(&mut self, st: &mut Gd<SurfaceTool>) {
-        let dim = self.get_terrain_dimensions();
-        let cell_size = self.get_cell_size();
-        let merge_threshold = MergeMode::from_index(self.merge_mode).threshold();
-        let blend_mode = self.get_blend_mode();
-        let use_ridge_texture = self.get_use_ridge_texture();
-        let ridge_threshold = self.get_ridge_threshold();
-
+    fn cell_config(
+        &self,
+        dimensions: Vector3i,
+        cell_size: Vector2,
+    ) -> marching_squares::CellConfig {
         let chunk_position = if self.base().is_inside_tree() {
             self.base().get_global_position()
         } else {
             self.base().get_position()
         };
 
+        marching_squares::CellConfig {
+            dimensions,
+            cell_size,
+            merge_threshold: MergeMode::from_index(self.merge_mode).threshold(),
+            higher_poly_floors: self.higher_poly_floors,
+            blend_mode: self.get_blend_mode(),
+            use_ridge_texture: self.get_use_ridge_texture(),
+            ridge_threshold: self.get_ridge_threshold(),
+            is_new_chunk: self.is_new_chunk,
+            chunk_position,
+        }
+    }
+
+    fn generate_terrain_cells(&mut self, st: &mut Gd<SurfaceTool>) {
+        let dim = self.get_terrain_dimensions();
+        let cell_size = self.get_cell_size();
+
         let mut ctx = CellContext {
-            config: marching_squares::CellConfig {
-                dimensions: dim,
-                cell_size,
-                merge_threshold,
-                higher_poly_floors: self.higher_poly_floors,
-                blend_mode,
-                use_ridge_texture,
-                ridge_threshold,
-                is_new_chunk: self.is_new_chunk,
-                chunk_position,
-            },
+            config: self.cell_config(dim, cell_size),
             color_maps: std::mem::take(&mut self.color_maps),
             ..Default::default()
         };
@@ -780,6 +907,11 @@ impl PixyTerrainChunk {
                     marching_squares::add_full_floor(&mut ctx, &mut geo);
                 }
 
+                marching_squares::remove_degenerate_triangles(
+                    &mut geo,
+                    self.terrain_config.degenerate_area_epsilon,
+                );
+
                 let _ = replay_geometry(st, &geo);
                 self.cell_geometry.insert(key, geo);
             }
@@ -793,6 +925,116 @@ impl PixyTerrainChunk {
         }
     }
 
+    /// Mesh every `step`-th heightmap vertex as one coarse cell, straight into `st`.
+    /// Nothing is cached, so edits and grass keep working off the full-resolution data;
+    /// `step == 1` gives the full-resolution surface.
+    /// Edges are not stitched: next to a finer neighbour the skipped seam vertices form
+    /// T-junctions, which can show as hairline cracks along the shared edge.
+    fn generate_lod_cells(&self, st: &mut Gd<SurfaceTool>, step: i32) {
+        let dim = self.get_terrain_dimensions();
+        let coarse_x = (dim.x - 1) / step + 1;
+        let coarse_z = (dim.z - 1) / step + 1;
+        let coarse_dim = Vector3i::new(coarse_x, dim.y, coarse_z);
+        let cell_size = self.get_cell_size() * step as f32;
+
+        let pick = |map: &[Color]| -> Vec<Color> {
+            let mut out = Vec::with_capacity((coarse_x * coarse_z) as usize);
+            for z in 0..coarse_z {
+                for x in 0..coarse_x {
+                    out.push(map[(z * step * dim.x + x * step) as usize]);
+                }
+            }
+            out
+        };
+        let maps = &self.color_maps;
+        let color_maps = marching_squares::ColorMaps {
+            color_0: pick(&maps.color_0),
+            color_1: pick(&maps.color_1),
+            wall_color_0: pick(&maps.wall_color_0),
+            wall_color_1: pick(&maps.wall_color_1),
+            grass_mask: pick(&maps.grass_mask),
+        };
+
+        let mut ctx = CellContext {
+            config: self.cell_config(coarse_dim, cell_size),
+            color_maps,
+            ..Default::default()
+        };
+
+        let height = |x: i32, z: i32| self.height_map[(z * step) as usize][(x * step) as usize];
+        for z in 0..(coarse_z - 1) {
+            for x in 0..(coarse_x - 1) {
+                ctx.heights = [
+                    height(x, z),
+                    height(x + 1, z),
+                    height(x + 1, z + 1),
+                    height(x, z + 1),
+                ];
+                ctx.edges = [true; 4];
+                ctx.rotation = 0;
+                ctx.cell_coords = Vector2i::new(x, z);
+                ctx.color_state = marching_squares::CellColorState::default();
+                ctx.floor_mode = true;
+
+                let mut geo = CellGeometry::default();
+                marching_squares::generate_cell(&mut ctx, &mut geo);
+                if geo.verts.len() % 3 != 0 {
+                    geo = CellGeometry::default();
+                    ctx.rotation = 0;
+                    marching_squares::add_full_floor(&mut ctx, &mut geo);
+                }
+                marching_squares::remove_degenerate_triangles(
+                    &mut geo,
+                    self.terrain_config.degenerate_area_epsilon,
+                );
+                let _ = replay_geometry(st, &geo);
+            }
+        }
+    }
+
+    /// Drop any existing collider and recreate it if this chunk should have one.
+    /// Called on every mesh regeneration and when the terrain's collision region changes.
+    /// Above LOD 0 the mesh is coarse, so the current body is kept as is until the chunk is
+    /// back at full resolution (edits made meanwhile reach the collider then).
+    pub fn rebuild_collision(&mut self) {
+        let (dim_x, dim_z) = self.get_dimensions_xz();
+        if lod_step(self.lod, dim_x, dim_z) > 1 {
+            return;
+        }
+
+        // Remove any existing StaticBody3D children (cleanup for re-generation)
+        let children = self.base().get_children_ex().include_internal(true).done();
+        for i in (0..children.len()).rev() {
+            if let Some(child) = children.get(i) {
+                if child.is_class("StaticBody3D") {
+                    let mut child = child;
+                    self.base_mut().remove_child(&child);
+                    child.queue_free();
+                }
+            }
+        }
+
+        if !self.wants_collision() || self.base().get_mesh().is_none() {
+            return;
+        }
+
+        // Create collision via Godot's built-in method, then configure it.
+        self.base_mut().create_trimesh_collision();
+        self.configure_collision();
+    }
+
+    fn wants_collision(&self) -> bool {
+        if !self.terrain_config.generate_collision {
+            return false;
+        }
+        let center = chunk_center_xz(
+            self.chunk_coords,
+            self.terrain_config.shared.dimensions,
+            self.terrain_config.shared.cell_size,
+        );
+        region_contains_xz(self.terrain_config.collision_region, center)
+    }
+
     fn configure_collision(&mut self) {
         let children = self.base().get_children_ex().include_internal(true).done();
         for i in 0..children.len() {
@@ -827,6 +1069,146 @@ impl PixyTerrainChunk {
     }
 }
 
+/// A `SurfaceTool` ready for terrain triangles, with the three RGBA custom channels.
+fn begin_surface_tool() -> Gd<SurfaceTool> {
+    let mut st = SurfaceTool::new_gd();
+    st.begin(PrimitiveType::TRIANGLES);
+    st.set_custom_format(0, CustomFormat::RGBA_FLOAT);
+    st.set_custom_format(1, CustomFormat::RGBA_FLOAT);
+    st.set_custom_format(2, CustomFormat::RGBA_FLOAT);
+    st
+}
+
+/// Vertex stride for mesh LOD `lod`: `2^lod`, halved until it evenly divides both grid
+/// axes so the coarse mesh still covers the whole chunk.
+pub fn lod_step(lod: u32, dim_x: i32, dim_z: i32) -> i32 {
+    let mut step = 1i32 << lod.min(8);
+    while step > 1 && ((dim_x - 1) % step != 0 || (dim_z - 1) % step != 0) {
+        step /= 2;
+    }
+    step
+}
+
+/// Bilinearly resample a `[z][x]` heightmap onto `new_x` x `new_z` vertices with the corner
+/// vertices aligned, so grid points that coincide with old vertices keep their exact height.
+fn resample_heights(src: &[Vec<f32>], new_x: usize, new_z: usize) -> Vec<Vec<f32>> {
+    let old_z = src.len();
+    let old_x = src.first().map_or(0, Vec::len);
+    if old_x == 0 || old_z == 0 || new_x == 0 || new_z == 0 {
+        return vec![vec![0.0; new_x]; new_z];
+    }
+    (0..new_z)
+        .map(|z| {
+            let (z0, z1, tz) = resample_span(z, old_z, new_z);
+            (0..new_x)
+                .map(|x| {
+                    let (x0, x1, tx) = resample_span(x, old_x, new_x);
+                    let top = lerp(src[z0][x0], src[z0][x1], tx);
+                    let bottom = lerp(src[z1][x0], src[z1][x1], tx);
+                    lerp(top, bottom, tz)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Nearest-neighbor resample of a row-major (`z * dim_x + x`) vertex map.
+fn resample_nearest<T: Copy>(
+    src: &[T],
+    old_x: usize,
+    old_z: usize,
+    new_x: usize,
+    new_z: usize,
+) -> Vec<T> {
+    if src.len() != old_x * old_z || src.is_empty() {
+        return Vec::new();
+    }
+    let mut out = Vec::with_capacity(new_x * new_z);
+    for z in 0..new_z {
+        let (z0, z1, tz) = resample_span(z, old_z, new_z);
+        let sz = if tz < 0.5 { z0 } else { z1 };
+        for x in 0..new_x {
+            let (x0, x1, tx) = resample_span(x, old_x, new_x);
+            let sx = if tx < 0.5 { x0 } else { x1 };
+            out.push(src[sz * old_x + sx]);
+        }
+    }
+    out
+}
+
+/// Old-grid neighbors `(i0, i1)` and blend factor for new-grid index `i`, corners aligned.
+fn resample_span(i: usize, old_len: usize, new_len: usize) -> (usize, usize, f32) {
+    if new_len < 2 || old_len < 2 {
+        return (0, 0, 0.0);
+    }
+    let u = i as f32 * (old_len - 1) as f32 / (new_len - 1) as f32;
+    let i0 = (u.floor() as usize).min(old_len - 1);
+    let i1 = (i0 + 1).min(old_len - 1);
+    (i0, i1, u - i0 as f32)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Heightmap value for a raw noise sample in `-1..=1`: scaled by `dimensions.y` and the
+/// configured amplitude, then terraced.
+pub fn noise_height(sample: f32, dim_y: i32, config: &TerrainConfig) -> f32 {
+    terrace(
+        sample * dim_y as f32 * config.noise_amplitude,
+        config.terrace_height,
+        config.terrace_sharpness,
+    )
+}
+
+/// Quantize `height` into steps of `step` with a smoothstep ramp up to the next step.
+/// The ramp takes the last `1 - sharpness` of each step; `step <= 0` returns `height` unchanged.
+fn terrace(height: f32, step: f32, sharpness: f32) -> f32 {
+    if step <= 0.0 {
+        return height;
+    }
+    let t = height / step;
+    let floor = t.floor();
+    let ramp_start = sharpness.clamp(0.0, 1.0);
+    let ramp = if ramp_start >= 1.0 {
+        0.0
+    } else {
+        let u = ((t - floor - ramp_start) / (1.0 - ramp_start)).clamp(0.0, 1.0);
+        u * u * (3.0 - 2.0 * u)
+    };
+    (floor + ramp) * step
+}
+
+/// Sample `height_at(world_x, world_z)` over a chunk's vertex grid, indexed `[z][x]`.
+fn bake_height_grid(
+    coords: Vector2i,
+    dimensions: Vector3i,
+    cell_size: Vector2,
+    mut height_at: impl FnMut(f32, f32) -> f32,
+) -> Vec<Vec<f32>> {
+    (0..dimensions.z)
+        .map(|z| {
+            (0..dimensions.x)
+                .map(|x| {
+                    let world =
+                        vertex_position([coords.x, coords.y], [x, z], dimensions, cell_size);
+                    height_at(world.x, world.y)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// True when no region is set, or when `point` lies inside the (inclusive) min/max rectangle.
+fn region_contains_xz(region: Option<(Vector2, Vector2)>, point: Vector2) -> bool {
+    match region {
+        None => true,
+        Some((min, max)) => {
+            point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+        }
+    }
+}
+
 fn replay_geometry(st: &mut Gd<SurfaceTool>, geo: &CellGeometry) -> bool {
     if geo.verts.len() % 3 != 0 {
         godot_warn!(
@@ -861,3 +1243,124 @@ fn replay_geometry(st: &mut Gd<SurfaceTool>, geo: &CellGeometry) -> bool {
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMS: Vector3i = Vector3i::new(33, 32, 33);
+    const CELL: Vector2 = Vector2::new(2.0, 2.0);
+
+    #[test]
+    fn test_resample_finer_preserves_heights() {
+        let old: Vec<Vec<f32>> = (0..5)
+            .map(|z| (0..5).map(|x| ((x * 7 + z * 3) % 5) as f32 * 1.5).collect())
+            .collect();
+        let fine = resample_heights(&old, 9, 9);
+        assert_eq!(fine.len(), 9);
+        assert!(fine.iter().all(|row| row.len() == 9));
+        for z in 0..5 {
+            for x in 0..5 {
+                assert_eq!(fine[z * 2][x * 2], old[z][x], "column ({x}, {z})");
+            }
+        }
+        // Midpoints between old vertices are their average
+        assert_eq!(fine[0][1], (old[0][0] + old[0][1]) * 0.5);
+
+        // Going back down recovers the original grid
+        assert_eq!(resample_heights(&fine, 5, 5), old);
+    }
+
+    #[test]
+    fn test_resample_nearest_keeps_corners() {
+        let old: Vec<u32> = (0..12).collect(); // 4 x 3, row-major
+        let out = resample_nearest(&old, 4, 3, 7, 5);
+        assert_eq!(out.len(), 35);
+        assert_eq!(out[0], 0);
+        assert_eq!(out[6], 3);
+        assert_eq!(out[28], 8);
+        assert_eq!(out[34], 11);
+    }
+
+    #[test]
+    fn test_terrace_clusters_heights_on_steps() {
+        let step = 4.0;
+        let heights: Vec<f32> = (0..400).map(|i| -10.0 + i as f32 * 0.05).collect();
+        let terraced: Vec<f32> = heights.iter().map(|&h| terrace(h, step, 0.75)).collect();
+
+        let near_step = terraced
+            .iter()
+            .filter(|&&h| (h / step - (h / step).round()).abs() < 0.05)
+            .count();
+        assert!(
+            near_step * 10 >= terraced.len() * 7,
+            "only {near_step} of {} heights on a terrace",
+            terraced.len()
+        );
+        // Terracing never reorders heights or moves one more than a step
+        assert!(terraced.windows(2).all(|w| w[0] <= w[1]));
+        assert!(heights
+            .iter()
+            .zip(&terraced)
+            .all(|(h, t)| (h - t).abs() <= step));
+    }
+
+    #[test]
+    fn test_noise_height_scales_with_amplitude() {
+        let mut config = TerrainConfig::default();
+        assert_eq!(noise_height(0.5, 32, &config), 16.0);
+        config.noise_amplitude = 0.25;
+        assert_eq!(noise_height(0.5, 32, &config), 4.0);
+        assert_eq!(noise_height(-1.0, 32, &config), -8.0);
+        config.noise_amplitude = 0.0;
+        assert_eq!(noise_height(0.9, 32, &config), 0.0);
+    }
+
+    #[test]
+    fn test_terrace_disabled_and_hard_steps() {
+        assert_eq!(terrace(7.3, 0.0, 0.5), 7.3);
+        assert_eq!(terrace(7.3, 2.0, 1.0), 6.0);
+        assert_eq!(terrace(-0.5, 2.0, 1.0), -2.0);
+    }
+
+    #[test]
+    fn test_bake_height_grid_matches_source_at_vertices() {
+        let source = |x: f32, z: f32| x * 0.5 - z * 0.25;
+        let coords = Vector2i::new(1, -1);
+        let grid = bake_height_grid(coords, DIMS, CELL, source);
+
+        assert_eq!(grid.len(), DIMS.z as usize);
+        assert_eq!(grid[0].len(), DIMS.x as usize);
+        // Vertex (0,0) of chunk (1,-1) sits at world (64, -64)
+        assert_eq!(grid[0][0], source(64.0, -64.0));
+        // Vertex (3,5) sits at world (70, -54)
+        assert_eq!(grid[5][3], source(70.0, -54.0));
+        // Last vertex shares the position of the neighboring chunk's first vertex
+        assert_eq!(grid[32][32], source(128.0, 0.0));
+    }
+
+    #[test]
+    fn test_no_region_allows_all_chunks() {
+        let center = chunk_center_xz(Vector2i::new(5, -7), DIMS, CELL);
+        assert!(region_contains_xz(None, center));
+    }
+
+    #[test]
+    fn test_region_selects_inside_chunk_only() {
+        let region = Some((Vector2::new(0.0, 0.0), Vector2::new(64.0, 64.0)));
+        let inside = chunk_center_xz(Vector2i::ZERO, DIMS, CELL);
+        let outside = chunk_center_xz(Vector2i::new(1, 0), DIMS, CELL);
+        assert!(region_contains_xz(region, inside));
+        assert!(!region_contains_xz(region, outside));
+    }
+
+    #[test]
+    fn test_lod_step_divides_the_grid() {
+        assert_eq!(lod_step(0, 33, 33), 1);
+        assert_eq!(lod_step(2, 33, 33), 4);
+        assert_eq!(lod_step(5, 33, 33), 32);
+        // 33 x 25 vertices: 8 divides both 32 and 24, 16 does not
+        assert_eq!(lod_step(4, 33, 25), 8);
+        assert_eq!(lod_step(3, 34, 34), 1);
+    }
+}
@@ -3,20 +3,22 @@
 // Original editor plugin design ported from Yugen's marching_squares_terrain_plugin.gd:
 //   https://github.com/Yukitty/Yugens-Terrain-Authoring-Toolkit
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use godot::classes::editor_plugin::AfterGuiInput;
 use godot::classes::editor_plugin::CustomControlContainer;
+use godot::classes::undo_redo::MergeMode;
 use godot::classes::{
-    Button, ButtonGroup, Camera3D, CenterContainer, CheckBox, ColorPickerButton, EditorPlugin,
-    EditorResourcePicker, HBoxContainer, HSeparator, HSlider, IEditorPlugin, Input, InputEvent,
-    InputEventKey, InputEventMouseButton, InputEventMouseMotion, Label, MarginContainer,
-    OptionButton, PhysicsRayQueryParameters3D, ScrollContainer, StaticBody3D, VBoxContainer,
-    VSeparator,
+    Button, ButtonGroup, Camera3D, CenterContainer, CheckBox, ColorPickerButton, Curve,
+    EditorPlugin, EditorResourcePicker, HBoxContainer, HSeparator, HSlider, IEditorPlugin, Input,
+    InputEvent, InputEventKey, InputEventMouseButton, InputEventMouseMotion, Label,
+    MarginContainer, OptionButton, PhysicsRayQueryParameters3D, ScrollContainer, StaticBody3D,
+    VBoxContainer, VSeparator,
 };
 use godot::prelude::*;
 
 use crate::gizmo::{self, GizmoState, PixyTerrainGizmoPlugin};
+use crate::grid::{global_vertex, vertex_position, world_to_chunk_cell};
 use crate::marching_squares;
 use crate::quick_paint::PixyQuickPaint;
 use crate::terrain::PixyTerrain;
@@ -37,6 +39,8 @@ const MAX_BRUSH_SIZE: f32 = 50.0;
 const MIN_BRUSH_SIZE: f32 = 1.0;
 /// Scroll wheel brush size step.
 const BRUSH_SIZE_STEP: f32 = 0.5;
+/// Deepest cross-chunk edge blend, in cells past the shared border (Edge slider maximum).
+const MAX_EDGE_DEPTH: i32 = 8;
 
 fn lerp_f32(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
@@ -68,6 +72,298 @@ fn godot_ease(x: f32, curve: f32) -> f32 {
     }
 }
 
+/// Bridge height at `progress` (0 at the start, 1 at the cursor) along the span.
+/// `ease_value` of -1 keeps the slope linear, anything else shapes it like `ease()`.
+pub(crate) fn bridge_height(start_y: f32, end_y: f32, progress: f32, ease_value: f32) -> f32 {
+    let mut progress = progress.clamp(0.0, 1.0);
+    if ease_value != -1.0 {
+        progress = godot_ease(progress, ease_value);
+    }
+    lerp_f32(start_y, end_y, progress)
+}
+
+/// Whether a stroke in `mode` also paints the Vertex Paint texture onto its ground cells.
+/// An active QuickPaint preset already paints the stroke, so it takes precedence.
+fn paints_texture_with_height(
+    mode: TerrainToolMode,
+    paint_texture: bool,
+    quick_paint: bool,
+) -> bool {
+    paint_texture && !quick_paint && is_height_mode(mode)
+}
+
+/// Phase 1 cells: the pattern entries whose chunk is `loaded`, in pattern order. A stroke
+/// writes its per-mode do/undo values for these cells.
+#[allow(clippy::type_complexity)]
+fn stroke_cells(
+    pattern: &[([i32; 2], Vec<([i32; 2], f32)>)],
+    loaded: impl Fn([i32; 2]) -> bool,
+) -> Vec<&([i32; 2], Vec<([i32; 2], f32)>)> {
+    pattern.iter().filter(|(chunk, _)| loaded(*chunk)).collect()
+}
+
+/// Combined stroke: the ground colors `(color_0, color_1)` to write under a height edit.
+/// Every pattern cell of a chunk that `current` can read gets `paint` as its do value and
+/// its current colors as the undo value: `chunk -> cell -> (do, undo)`.
+#[allow(clippy::type_complexity)]
+fn combined_stroke_colors(
+    pattern: &[([i32; 2], Vec<([i32; 2], f32)>)],
+    paint: (Color, Color),
+    current: impl Fn([i32; 2], [i32; 2]) -> Option<(Color, Color)>,
+) -> HashMap<[i32; 2], HashMap<[i32; 2], ((Color, Color), (Color, Color))>> {
+    let mut out: HashMap<[i32; 2], HashMap<[i32; 2], _>> = HashMap::new();
+    for (chunk, cells) in pattern {
+        for &(cell, _) in cells {
+            if let Some(old) = current(*chunk, cell) {
+                out.entry(*chunk).or_default().insert(cell, (paint, old));
+            }
+        }
+    }
+    out
+}
+
+/// Flatten a draw pattern into (chunk, cell, sample) triples in a stable order.
+#[allow(clippy::type_complexity)]
+fn footprint_cells(
+    pattern: &HashMap<[i32; 2], HashMap<[i32; 2], f32>>,
+) -> Vec<([i32; 2], [i32; 2], f32)> {
+    let mut cells: Vec<_> = pattern
+        .iter()
+        .flat_map(|(&chunk, cells)| cells.iter().map(move |(&cell, &s)| (chunk, cell, s)))
+        .collect();
+    cells.sort_by_key(|&(chunk, cell, _)| (chunk[1], chunk[0], cell[1], cell[0]));
+    cells
+}
+
+/// Whether strokes in `mode` write heights.
+fn is_height_mode(mode: TerrainToolMode) -> bool {
+    matches!(
+        mode,
+        TerrainToolMode::Height
+            | TerrainToolMode::Sculpt
+            | TerrainToolMode::Level
+            | TerrainToolMode::Contour
+            | TerrainToolMode::Step
+            | TerrainToolMode::Smooth
+            | TerrainToolMode::Sharpen
+            | TerrainToolMode::Bridge
+            | TerrainToolMode::Clone
+    )
+}
+
+/// Whether height strokes in `mode` blend a few cells past a chunk border (the Edge slider).
+/// Step is left out: partial blends would leave heights between its snapped steps.
+fn blends_into_neighbors(mode: TerrainToolMode) -> bool {
+    is_height_mode(mode) && mode != TerrainToolMode::Step
+}
+
+/// Cells `1..=depth` steps into a neighbor chunk from its shared border cell `border`,
+/// walking in direction `dir` (the neighbor's offset from the source chunk).
+/// Blend falls off linearly so the last cell gets the least; depth 1 gives 0.5.
+fn edge_inner_cells(
+    border: [i32; 2],
+    dir: [i32; 2],
+    depth: i32,
+    dim: Vector3i,
+) -> Vec<([i32; 2], f32)> {
+    let depth = depth.clamp(1, MAX_EDGE_DEPTH);
+    (1..=depth)
+        .map(|step| {
+            let cell = [border[0] + dir[0] * step, border[1] + dir[1] * step];
+            (cell, 1.0 - step as f32 / (depth + 1) as f32)
+        })
+        .take_while(|&(c, _)| c[0] >= 0 && c[0] < dim.x && c[1] >= 0 && c[1] < dim.z)
+        .collect()
+}
+
+/// Unsharp mask for one height sample: push `current` away from its neighbor average.
+/// The change is clamped to `max_delta` so repeated strokes can't run away.
+fn sharpen_height(current: f32, neighbor_avg: f32, amount: f32, max_delta: f32) -> f32 {
+    let delta = ((current - neighbor_avg) * amount).clamp(-max_delta, max_delta);
+    current + delta
+}
+
+/// Clone tool: the source position for a destination cell keeps the same offset
+/// from the clone source as the cell has from the start of the stroke.
+fn clone_source_position(source: Vector2, stroke_origin: Vector2, cell_world: Vector2) -> Vector2 {
+    source + (cell_world - stroke_origin)
+}
+
+/// Whether vertex `cell` of `chunk` may be edited: its terrain-wide vertex is in `selection`
+/// and its position inside the XZ rectangle `region`, for whichever of the two is set.
+fn is_editable(
+    chunk: [i32; 2],
+    cell: [i32; 2],
+    selection: Option<&HashSet<[i32; 2]>>,
+    region: Option<(Vector2, Vector2)>,
+    dim: Vector3i,
+    cell_size: Vector2,
+) -> bool {
+    if let Some(selection) = selection {
+        if !selection.contains(&global_vertex(chunk, cell, dim)) {
+            return false;
+        }
+    }
+    if let Some((min, max)) = region {
+        let p = vertex_position(chunk, cell, dim, cell_size);
+        return p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y;
+    }
+    true
+}
+
+/// Drop pattern cells whose terrain-wide vertex is outside `selection`.
+fn retain_selected(
+    pattern: &mut HashMap<[i32; 2], HashMap<[i32; 2], f32>>,
+    selection: &HashSet<[i32; 2]>,
+    dim: Vector3i,
+) {
+    for (chunk, cells) in pattern.iter_mut() {
+        cells
+            .retain(|cell, _| is_editable(*chunk, *cell, Some(selection), None, dim, Vector2::ONE));
+    }
+    pattern.retain(|_, cells| !cells.is_empty());
+}
+
+/// Drop pattern cells whose vertex lies outside the terrain-local XZ rectangle [min, max].
+fn retain_in_region(
+    pattern: &mut HashMap<[i32; 2], HashMap<[i32; 2], f32>>,
+    region: (Vector2, Vector2),
+    dim: Vector3i,
+    cell_size: Vector2,
+) {
+    for (chunk, cells) in pattern.iter_mut() {
+        cells.retain(|cell, _| is_editable(*chunk, *cell, None, Some(region), dim, cell_size));
+    }
+    pattern.retain(|_, cells| !cells.is_empty());
+}
+
+/// Brush snap: move a raycast hit onto the nearest heightmap vertex, at that vertex's
+/// stored height, so the brush sits exactly on the terrain.
+fn snap_to_vertex(
+    terrain: &Gd<PixyTerrain>,
+    local_pos: Vector3,
+    dim: Vector3i,
+    cell_size: Vector2,
+) -> Vector3 {
+    let (chunk, cell) = world_to_chunk_cell(Vector2::new(local_pos.x, local_pos.z), dim, cell_size);
+    let Some(chunk_gd) = terrain.bind().get_chunk(chunk[0], chunk[1]) else {
+        return local_pos;
+    };
+    let height = chunk_gd.bind().get_height(Vector2i::new(cell[0], cell[1]));
+    let xz = vertex_position(chunk, cell, dim, cell_size);
+    Vector3::new(xz.x, height, xz.y)
+}
+
+/// Sculpt tool: move `current` toward `target` by at most `max_step`, never past it.
+fn sculpt_step(current: f32, target: f32, max_step: f32) -> f32 {
+    let delta = target - current;
+    if delta.abs() <= max_step.abs() {
+        target
+    } else {
+        current + max_step.abs().copysign(delta)
+    }
+}
+
+/// Brush weight at normalized distance `t` (0 = rim, 1 = center). Samples the artist's
+/// falloff `curve` when one is set, else smoothstep (stand-in for Yugen's default Curve).
+fn falloff_weight(t: f32, curve: Option<impl Fn(f32) -> f32>) -> f32 {
+    let t = t.clamp(0.001, 0.999);
+    match curve {
+        Some(sample) => sample(t).clamp(0.0, 1.0),
+        None => t * t * (3.0 - 2.0 * t),
+    }
+}
+
+/// Chunk tool rule: a new chunk must share an edge with an existing one, except the first.
+fn can_add_chunk(
+    x: i32,
+    z: i32,
+    terrain_empty: bool,
+    has_chunk: impl Fn(i32, i32) -> bool,
+) -> bool {
+    terrain_empty
+        || has_chunk(x - 1, z)
+        || has_chunk(x + 1, z)
+        || has_chunk(x, z - 1)
+        || has_chunk(x, z + 1)
+}
+
+/// Mean of `heights`, or 0 when empty. Target for Smooth and averaged Level strokes.
+fn mean_height(heights: &[f32]) -> f32 {
+    heights.iter().sum::<f32>() / heights.len().max(1) as f32
+}
+
+/// Contour tool: offset a vertex from its pre-stroke height by `depth` (down, or up when
+/// `raise`), scaled by the brush sample, so the edit follows the existing slope.
+fn contour_height(original: f32, depth: f32, sample: f32, raise: bool) -> f32 {
+    let offset = depth.abs() * sample;
+    if raise {
+        original + offset
+    } else {
+        original - offset
+    }
+}
+
+/// Step tool: snap `current` to the nearest multiple of `step`, then move one `step` up
+/// (or down, when `lower`). Repeated clicks land on whole steps.
+fn step_height(current: f32, step: f32, lower: bool) -> f32 {
+    let step = step.abs();
+    if step <= f32::EPSILON {
+        return current;
+    }
+    let snapped = (current / step).round() * step;
+    if lower {
+        snapped - step
+    } else {
+        snapped + step
+    }
+}
+
+/// PaintLimits: the `(min, max)` limit a stroke paints, or `None` when it clears limits.
+fn painted_limit(min: f32, max: f32, clear: bool) -> Option<Vector2> {
+    (!clear).then(|| Vector2::new(min.min(max), min.max(max)))
+}
+
+/// Terrain-wide vertices under a draw pattern, sorted; seam vertices stored by two chunks
+/// appear once.
+fn footprint_vertices(
+    pattern: &HashMap<[i32; 2], HashMap<[i32; 2], f32>>,
+    dim: Vector3i,
+) -> Vec<[i32; 2]> {
+    let mut vertices: Vec<[i32; 2]> = pattern
+        .iter()
+        .flat_map(|(chunk, cells)| {
+            cells
+                .keys()
+                .map(move |cell| global_vertex(*chunk, *cell, dim))
+        })
+        .collect();
+    vertices.sort_unstable();
+    vertices.dedup();
+    vertices
+}
+
+/// Additive sculpt: build up (or dig, when `lower`) by `step` on every application, with no target.
+fn sculpt_add(current: f32, step: f32, lower: bool) -> f32 {
+    if lower {
+        current - step.abs()
+    } else {
+        current + step.abs()
+    }
+}
+
+/// Colors a vertex paint stroke writes; erasing restores the default texture a new chunk starts with.
+fn vertex_paint_colors(erase: bool, selected: (Color, Color)) -> (Color, Color) {
+    if erase {
+        (
+            marching_squares::DEFAULT_TEXTURE_COLOR,
+            marching_squares::DEFAULT_TEXTURE_COLOR,
+        )
+    } else {
+        selected
+    }
+}
+
 // =======================================
 // Enums
 // =======================================
@@ -83,6 +379,38 @@ pub enum TerrainToolMode {
     VertexPaint = 5,
     DebugBrush = 6,
     ChunkManagement = 7,
+    Clone = 8,
+    Sharpen = 9,
+    Sculpt = 10,
+    Contour = 11,
+    Step = 12,
+    PaintLimits = 13,
+}
+
+/// Toolbar order of the tool mode buttons. `on_tool_button_toggled` receives an index into this.
+const TOOL_MODES: [TerrainToolMode; 14] = [
+    TerrainToolMode::Height,
+    TerrainToolMode::Sculpt,
+    TerrainToolMode::Level,
+    TerrainToolMode::Contour,
+    TerrainToolMode::Step,
+    TerrainToolMode::Smooth,
+    TerrainToolMode::Sharpen,
+    TerrainToolMode::Bridge,
+    TerrainToolMode::Clone,
+    TerrainToolMode::PaintLimits,
+    TerrainToolMode::GrassMask,
+    TerrainToolMode::VertexPaint,
+    TerrainToolMode::DebugBrush,
+    TerrainToolMode::ChunkManagement,
+];
+
+/// Toolbar index of the grass mask button (re-clicks toggle Add/Remove).
+fn grass_tool_index() -> usize {
+    TOOL_MODES
+        .iter()
+        .position(|m| *m == TerrainToolMode::GrassMask)
+        .unwrap_or(0)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -92,6 +420,57 @@ pub enum BrushType {
     Square = 1,
 }
 
+/// A texture-panel setting on `PixyTerrain`, parsed from its attribute name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TerrainSetting {
+    /// `texture_scales[slot]`, 0-based (`tex_scale_N` is slot N - 1).
+    TextureScale(usize),
+    /// `texN_has_grass`, N in 2..=6 (texture 1 always has grass).
+    HasGrass(usize),
+    /// `ground_colors[slot]`, 0-based (`ground_color_N` is slot N - 1).
+    GroundColor(usize),
+}
+
+impl TerrainSetting {
+    fn parse(name: &str) -> Option<Self> {
+        let number = |prefix: &str| name.strip_prefix(prefix)?.parse::<usize>().ok();
+        if let Some(n) = number("tex_scale_") {
+            return n.checked_sub(1).map(Self::TextureScale);
+        }
+        if let Some(n) = number("tex_has_grass_") {
+            return (2..=6).contains(&n).then_some(Self::HasGrass(n));
+        }
+        if let Some(n) = number("ground_color_") {
+            return n.checked_sub(1).map(Self::GroundColor);
+        }
+        None
+    }
+
+    /// Exported `PixyTerrain` property the setting lives in; undo/redo swaps it whole.
+    fn property(self) -> String {
+        match self {
+            Self::TextureScale(_) => "texture_scales".to_string(),
+            Self::HasGrass(n) => format!("tex{n}_has_grass"),
+            Self::GroundColor(_) => "ground_colors".to_string(),
+        }
+    }
+
+    /// The setting's own value inside its whole exported `property` value.
+    fn widget_value(self, property: &Variant) -> Option<Variant> {
+        match self {
+            Self::TextureScale(slot) => {
+                let scales: PackedFloat32Array = property.try_to().ok()?;
+                scales.get(slot).map(|v| (v as f64).to_variant())
+            }
+            Self::HasGrass(_) => Some(property.clone()),
+            Self::GroundColor(slot) => {
+                let colors: PackedColorArray = property.try_to().ok()?;
+                colors.get(slot).map(|c| c.to_variant())
+            }
+        }
+    }
+}
+
 // =======================================
 // Plugin Struct
 // =======================================
@@ -128,8 +507,49 @@ pub struct PixyTerrainPlugin {
     brush_type: BrushType,
     #[init(val = 15.0)]
     brush_size: f32,
+    #[init(val = false)]
+    snap_to_surface: bool,
     #[init(val = 1.0)]
     strength: f32,
+    /// Sculpt mode: how far above (or below, with Ctrl) the stroke start to build toward.
+    #[init(val = 5.0)]
+    sculpt_offset: f32,
+    /// Sculpt mode: height the current stroke approaches.
+    #[init(val = 0.0)]
+    sculpt_target: f32,
+    /// Sculpt mode: keep raising (lowering) on every application instead of stopping at the target.
+    #[init(val = false)]
+    sculpt_additive: bool,
+    /// Sculpt mode: the current stroke lowers (Ctrl was held on press).
+    #[init(val = false)]
+    sculpt_lower: bool,
+    /// Level mode: level to the footprint's mean height instead of `height`.
+    #[init(val = false)]
+    level_average: bool,
+    /// Contour mode: how far below (or above, with Ctrl) the existing surface to move it.
+    #[init(val = 2.0)]
+    contour_depth: f32,
+    /// Height modes: how many cells past a chunk border a stroke blends into the neighbor.
+    #[init(val = 1)]
+    edge_depth: i32,
+    /// Contour mode: the current stroke raises (Ctrl was held on press).
+    #[init(val = false)]
+    contour_raise: bool,
+    /// Step mode: height of one step; painted cells snap to multiples of it.
+    #[init(val = 1.0)]
+    step_size: f32,
+    /// Step mode: the current stroke lowers (Ctrl was held on press).
+    #[init(val = false)]
+    step_lower: bool,
+    /// PaintLimits mode: lowest height brushes may write to painted vertices.
+    #[init(val = -10.0)]
+    limit_min: f32,
+    /// PaintLimits mode: highest height brushes may write to painted vertices.
+    #[init(val = 10.0)]
+    limit_max: f32,
+    /// PaintLimits mode: the current stroke clears limits (Ctrl was held on press).
+    #[init(val = false)]
+    limit_clear: bool,
     /// Target height for Level mode.
     #[init(val = 0.0)]
     height: f32,
@@ -137,6 +557,8 @@ pub struct PixyTerrainPlugin {
     flatten: bool,
     #[init(val = true)]
     falloff: bool,
+    /// Artist-supplied falloff shape sampled at 0 (edge) ..= 1 (center); smoothstep when unset.
+    falloff_curve: Option<Gd<Curve>>,
     /// Ease value for bridge mode (-1.0 = no ease).
     #[init(val = -1.0)]
     ease_value: f32,
@@ -152,6 +574,11 @@ pub struct PixyTerrainPlugin {
     vertex_color_1: Color,
     #[init(val = false)]
     paint_walls_mode: bool,
+    #[init(val = false)]
+    erase_paint_mode: bool,
+    /// Height modes: also paint the Vertex Paint material onto the stroke's ground, in the same action.
+    #[init(val = false)]
+    paint_with_height: bool,
 
     // Drawing state
     #[init(val = Vector3::ZERO)]
@@ -191,6 +618,14 @@ pub struct PixyTerrainPlugin {
     #[init(val = Vector2i::ZERO)]
     bridge_start_chunk: Vector2i,
 
+    // Clone state
+    /// Source point set with Ctrl+Click in Clone mode (terrain-local).
+    #[init(val = None)]
+    clone_source: Option<Vector3>,
+    /// Brush position at the start of the current clone stroke.
+    #[init(val = Vector3::ZERO)]
+    clone_stroke_origin: Vector3,
+
     // QuickPaint presets
     #[init(val = Vec::new())]
     quick_paint_presets: Vec<Gd<PixyQuickPaint>>,
@@ -258,9 +693,15 @@ impl IEditorPlugin for PixyTerrainPlugin {
         let button_group = ButtonGroup::new_gd();
         let tool_labels = [
             "Height",
+            "Sculpt",
             "Level",
+            "Contour",
+            "Step",
             "Smooth",
+            "Sharpen",
             "Slope",
+            "Clone",
+            "Limits",
             "Add Grass",
             "Vertex Paint",
             "Debug",
@@ -273,17 +714,41 @@ impl IEditorPlugin for PixyTerrainPlugin {
                \u{2022} Shift+Click+Drag: Paint selection continuously\n\
                \u{2022} Shift+Scroll: Adjust brush size\n\
                \u{2022} Alt: Clear current selection",
+            "Sculpt Tool\n\nRaise terrain continuously while
+  dragging.\n\n[Shortcuts]\n\
+               \u{2022} Click+Drag: Raise toward stroke start + Raise\n\
+               \u{2022} Ctrl+Click+Drag: Lower instead\n\
+               \u{2022} Strength: height change per application",
             "Level Tool\n\nSet terrain to a specific
   height.\n\n[Shortcuts]\n\
                \u{2022} Ctrl+Click: Sample height from terrain\n\
-               \u{2022} Shift+Click+Drag: Paint at set height",
+               \u{2022} Shift+Click+Drag: Paint at set height\n\
+               \u{2022} Average: level to the stroke's mean height",
+            "Contour Tool\n\nLower terrain by a constant depth that
+  follows the existing slope.\n\n[Shortcuts]\n\
+               \u{2022} Click+Drag: Carve Depth below the surface\n\
+               \u{2022} Ctrl+Click+Drag: Raise by Depth instead",
+            "Step Tool\n\nRaise terrain by exactly one step per
+  click.\n\n[Shortcuts]\n\
+               \u{2022} Click+Drag: Snap to the step grid, then raise one Step\n\
+               \u{2022} Ctrl+Click+Drag: Lower one Step instead",
             "Smooth Tool\n\nSmooth out rough terrain
   areas.\n\n[Shortcuts]\n\
                \u{2022} Shift+Click+Drag: Smooth terrain",
+            "Sharpen Tool\n\nAccentuate ridges and dips that were
+  over-smoothed.\n\n[Shortcuts]\n\
+               \u{2022} Click+Drag: Sharpen terrain",
             "Slope Tool\n\nCreate slopes between two
   points.\n\n[Shortcuts]\n\
                \u{2022} Click start, drag to end\n\u{2022} Ease controls slope
    curve",
+            "Clone Tool\n\nCopy terrain height from a source
+  area.\n\n[Shortcuts]\n\
+               \u{2022} Ctrl+Click: Set clone source\n\
+               \u{2022} Click+Drag: Paint heights copied from the source",
+            "Limits Tool\n\nPaint height limits that brushes can't go past.\n\n[Shortcuts]\n\
+               \u{2022} Click+Drag: Limit heights to Min..Max\n\
+               \u{2022} Ctrl+Click+Drag: Clear limits",
             "Grass Tool\n\nAdd or remove grass on terrain.\n\nClick again to toggle between Add/Remove.",
             "Vertex Paint Tool\n\nPaint texture materials on
   terrain.\n\n[Shortcuts]\n\
@@ -302,12 +767,17 @@ impl IEditorPlugin for PixyTerrainPlugin {
 
         for (i, label) in tool_labels.iter().enumerate() {
             // Add labeled group headers before visual, utility, and management groups
-            if i == 4 {
-                Self::add_toolbar_group_label(&mut toolbar, "Visuals");
-            } else if i == 6 {
-                Self::add_toolbar_group_label(&mut toolbar, "Utility");
-            } else if i == 7 {
-                Self::add_toolbar_group_label(&mut toolbar, "Management");
+            match TOOL_MODES[i] {
+                TerrainToolMode::GrassMask => {
+                    Self::add_toolbar_group_label(&mut toolbar, "Visuals")
+                }
+                TerrainToolMode::DebugBrush => {
+                    Self::add_toolbar_group_label(&mut toolbar, "Utility")
+                }
+                TerrainToolMode::ChunkManagement => {
+                    Self::add_toolbar_group_label(&mut toolbar, "Management")
+                }
+                _ => {}
             }
 
             let mut btn = Button::new_alloc();
@@ -323,7 +793,7 @@ impl IEditorPlugin for PixyTerrainPlugin {
 
             toolbar.add_child(&btn);
 
-            if i == 4 {
+            if i == grass_tool_index() {
                 let down_callable =
                     Callable::from_object_method(&plugin_ref, "on_grass_mask_button_down");
                 btn.connect("button_down", &down_callable);
@@ -563,9 +1033,15 @@ impl IEditorPlugin for PixyTerrainPlugin {
         let is_draw_mode = matches!(
             self.mode,
             TerrainToolMode::Height
+                | TerrainToolMode::Sculpt
                 | TerrainToolMode::Level
+                | TerrainToolMode::Contour
+                | TerrainToolMode::Step
                 | TerrainToolMode::Smooth
+                | TerrainToolMode::Sharpen
                 | TerrainToolMode::Bridge
+                | TerrainToolMode::Clone
+                | TerrainToolMode::PaintLimits
                 | TerrainToolMode::GrassMask
                 | TerrainToolMode::VertexPaint
                 | TerrainToolMode::DebugBrush
@@ -614,7 +1090,12 @@ impl IEditorPlugin for PixyTerrainPlugin {
                         if !result.is_empty() {
                             if let Some(pos_variant) = result.get("position") {
                                 let world_pos: Vector3 = pos_variant.to();
-                                draw_position = Some(terrain_gd.to_local(world_pos));
+                                let local_pos = terrain_gd.to_local(world_pos);
+                                draw_position = Some(if self.snap_to_surface {
+                                    snap_to_vertex(&terrain, local_pos, dim, cell_size)
+                                } else {
+                                    local_pos
+                                });
                             }
                         }
                     }
@@ -660,7 +1141,13 @@ impl IEditorPlugin for PixyTerrainPlugin {
                                 (self.brush_position.z / chunk_depth).floor() as i32,
                             );
                         }
-                        if self.mode == TerrainToolMode::Smooth && !self.falloff {
+                        if matches!(
+                            self.mode,
+                            TerrainToolMode::Smooth
+                                | TerrainToolMode::Sharpen
+                                | TerrainToolMode::Sculpt
+                        ) && !self.falloff
+                        {
                             self.falloff = true;
                         }
                         if matches!(
@@ -672,7 +1159,8 @@ impl IEditorPlugin for PixyTerrainPlugin {
                         }
                         if matches!(
                             self.mode,
-                            TerrainToolMode::GrassMask
+                            TerrainToolMode::Clone
+                                | TerrainToolMode::GrassMask
                                 | TerrainToolMode::VertexPaint
                                 | TerrainToolMode::DebugBrush
                         ) && self.flatten
@@ -683,6 +1171,34 @@ impl IEditorPlugin for PixyTerrainPlugin {
                         if self.mode == TerrainToolMode::Level && ctrl_held {
                             // Ctrl+click in Level mode: set target height from click pos
                             self.height = self.brush_position.y;
+                        } else if self.mode == TerrainToolMode::Clone && ctrl_held {
+                            // Ctrl+click in Clone mode: set the clone source
+                            self.clone_source = Some(self.brush_position);
+                        } else if self.mode == TerrainToolMode::Clone && self.clone_source.is_none()
+                        {
+                            godot_warn!("Clone tool: Ctrl+Click to set a clone source first");
+                        } else if self.mode == TerrainToolMode::Sculpt {
+                            // Sculpt: one-phase drag toward a target above (Ctrl: below) the click
+                            let offset = if ctrl_held {
+                                -self.sculpt_offset
+                            } else {
+                                self.sculpt_offset
+                            };
+                            self.sculpt_target = self.brush_position.y + offset;
+                            self.sculpt_lower = ctrl_held;
+                            self.is_drawing = true;
+                        } else if self.mode == TerrainToolMode::Contour {
+                            // Contour: carve (Ctrl: raise) relative to the surface, applied on release
+                            self.contour_raise = ctrl_held;
+                            self.is_drawing = true;
+                        } else if self.mode == TerrainToolMode::Step {
+                            // Step: one whole step up (Ctrl: down) per click, applied on release
+                            self.step_lower = ctrl_held;
+                            self.is_drawing = true;
+                        } else if self.mode == TerrainToolMode::PaintLimits {
+                            // PaintLimits: set (Ctrl: clear) limits under the stroke, applied on release
+                            self.limit_clear = ctrl_held;
+                            self.is_drawing = true;
                         } else if shift_held {
                             // Shift+click: enter drawing mode
                             self.is_drawing = true;
@@ -690,11 +1206,13 @@ impl IEditorPlugin for PixyTerrainPlugin {
                             self.mode,
                             TerrainToolMode::Level
                                 | TerrainToolMode::Smooth
+                                | TerrainToolMode::Sharpen
                                 | TerrainToolMode::Bridge
+                                | TerrainToolMode::Clone
                                 | TerrainToolMode::GrassMask
                                 | TerrainToolMode::VertexPaint
                         ) {
-                            // Level/Smooth/Slope/GrassMask/VertexPaint: simple click-drag-release
+                            // Level/Smooth/Sharpen/Slope/Clone/GrassMask/VertexPaint: simple click-drag-release
                             self.is_drawing = true;
                         } else {
                             // Normal click: enter setting mode (two-click workflow)
@@ -704,6 +1222,10 @@ impl IEditorPlugin for PixyTerrainPlugin {
                             }
                         }
 
+                        if self.mode == TerrainToolMode::Clone && self.is_drawing {
+                            self.clone_stroke_origin = self.brush_position;
+                        }
+
                         // Initialize draw state
                         self.initialize_draw_state(&terrain, dim, cell_size);
 
@@ -722,7 +1244,11 @@ impl IEditorPlugin for PixyTerrainPlugin {
                                 self.mode,
                                 TerrainToolMode::GrassMask
                                     | TerrainToolMode::Level
+                                    | TerrainToolMode::Contour
+                                    | TerrainToolMode::Step
+                                    | TerrainToolMode::PaintLimits
                                     | TerrainToolMode::Bridge
+                                    | TerrainToolMode::Clone
                                     | TerrainToolMode::DebugBrush
                             ) {
                                 self.draw_pattern(&terrain, dim, cell_size);
@@ -730,7 +1256,10 @@ impl IEditorPlugin for PixyTerrainPlugin {
                             }
                             if matches!(
                                 self.mode,
-                                TerrainToolMode::Smooth | TerrainToolMode::VertexPaint
+                                TerrainToolMode::Smooth
+                                    | TerrainToolMode::Sharpen
+                                    | TerrainToolMode::Sculpt
+                                    | TerrainToolMode::VertexPaint
                             ) {
                                 self.current_draw_pattern.clear();
                             }
@@ -782,6 +1311,8 @@ impl IEditorPlugin for PixyTerrainPlugin {
                 if matches!(
                     self.mode,
                     TerrainToolMode::Smooth
+                        | TerrainToolMode::Sharpen
+                        | TerrainToolMode::Sculpt
                         | TerrainToolMode::VertexPaint
                         | TerrainToolMode::GrassMask
                 ) {
@@ -825,11 +1356,12 @@ impl IEditorPlugin for PixyTerrainPlugin {
                         } else {
                             // Add new chunk if adjacent to existing
                             let t = terrain.bind();
-                            let can_add = t.get_chunk_keys().is_empty()
-                                || t.has_chunk(chunk_x - 1, chunk_z)
-                                || t.has_chunk(chunk_x + 1, chunk_z)
-                                || t.has_chunk(chunk_x, chunk_z - 1)
-                                || t.has_chunk(chunk_x, chunk_z + 1);
+                            let can_add = can_add_chunk(
+                                chunk_x,
+                                chunk_z,
+                                t.get_chunk_keys().is_empty(),
+                                |x, z| t.has_chunk(x, z),
+                            );
                             drop(t);
 
                             if can_add {
@@ -876,6 +1408,70 @@ impl PixyTerrainPlugin {
         self.do_clear();
     }
 
+    /// Add the chunk at (x, z) to the selected terrain as an undoable "add chunk" action, for
+    /// scripted layouts. Same rule as the Chunk tool: the slot must be free and edge-adjacent
+    /// to an existing chunk, unless the terrain is empty. Returns whether it was added.
+    #[func]
+    pub fn add_chunk_undoable(&mut self, x: i32, z: i32) -> bool {
+        let Some(terrain) = self.selected_terrain() else {
+            return false;
+        };
+        let t = terrain.bind();
+        let allowed = !t.has_chunk(x, z)
+            && can_add_chunk(x, z, t.get_chunk_keys().is_empty(), |ax, az| {
+                t.has_chunk(ax, az)
+            });
+        drop(t);
+        if allowed {
+            self.register_chunk_undo_redo(&terrain.upcast::<Node>(), x, z, "add chunk", false);
+        }
+        allowed
+    }
+
+    /// Remove the chunk at (x, z) from the selected terrain as an undoable "remove chunk"
+    /// action. Returns whether a chunk was there.
+    #[func]
+    pub fn remove_chunk_undoable(&mut self, x: i32, z: i32) -> bool {
+        let Some(terrain) = self.selected_terrain() else {
+            return false;
+        };
+        if !terrain.bind().has_chunk(x, z) {
+            return false;
+        }
+        self.register_chunk_undo_redo(&terrain.upcast::<Node>(), x, z, "remove chunk", true);
+        true
+    }
+
+    /// Terrain-local positions of the cells the active stroke touches, at their current
+    /// height, for custom overlays. Empty between strokes. Sorted by chunk then cell.
+    #[func]
+    pub fn get_brush_footprint(&self) -> PackedVector3Array {
+        let Some(terrain) = self.selected_terrain() else {
+            return PackedVector3Array::new();
+        };
+        let t = terrain.bind();
+        footprint_cells(&self.current_draw_pattern)
+            .into_iter()
+            .map(|(chunk_key, cell_key, _)| {
+                let xz = vertex_position(chunk_key, cell_key, t.dimensions, t.cell_size);
+                let y = t
+                    .get_chunk(chunk_key[0], chunk_key[1])
+                    .map(|c| c.bind().get_height(Vector2i::new(cell_key[0], cell_key[1])))
+                    .unwrap_or(0.0);
+                Vector3::new(xz.x, y, xz.y)
+            })
+            .collect()
+    }
+
+    /// Falloff weight (0..1) of each cell in `get_brush_footprint`, in the same order.
+    #[func]
+    pub fn get_brush_footprint_weights(&self) -> PackedFloat32Array {
+        footprint_cells(&self.current_draw_pattern)
+            .into_iter()
+            .map(|(_, _, sample)| sample)
+            .collect()
+    }
+
     #[func]
     fn on_collision_toggle_changed(&mut self, pressed: bool) {
         self.show_collision_wireframes = pressed;
@@ -901,31 +1497,70 @@ impl PixyTerrainPlugin {
         self.rebuild_texture_panel_impl(plugin_ref);
     }
 
+    /// Show the terrain's current value of one texture-panel setting in its widget, without
+    /// emitting change signals. Undo/redo call this; an in-progress drag is left alone.
+    #[func]
+    fn _sync_texture_setting_widget(&mut self, setting_name: GString) {
+        let name = setting_name.to_string();
+        let (Some(setting), Some(panel), Some(terrain)) = (
+            TerrainSetting::parse(&name),
+            self.texture_panel.clone(),
+            self.current_terrain.clone(),
+        ) else {
+            return;
+        };
+        if !terrain.is_instance_valid() {
+            return;
+        }
+        let Some(value) = setting.widget_value(&terrain.get(setting.property().as_str())) else {
+            return;
+        };
+        let Some(widget) = panel
+            .find_child_ex(&setting_name)
+            .recursive(true)
+            .owned(false)
+            .done()
+        else {
+            return;
+        };
+        match setting {
+            TerrainSetting::TextureScale(_) => {
+                let v = value.to::<f64>();
+                if let Ok(mut slider) = widget.try_cast::<HSlider>() {
+                    slider.set_value_no_signal(v);
+                }
+                Self::update_slider_label(&panel, &name, "Scale", v);
+            }
+            TerrainSetting::HasGrass(_) => {
+                if let Ok(mut checkbox) = widget.try_cast::<CheckBox>() {
+                    checkbox.set_pressed_no_signal(value.to());
+                }
+            }
+            TerrainSetting::GroundColor(_) => {
+                if let Ok(mut picker) = widget.try_cast::<ColorPickerButton>() {
+                    picker.set_pick_color(value.to());
+                }
+            }
+        }
+    }
+
     /// Called when a tool mode toggle button is pressed.
     #[func]
     fn on_tool_button_toggled(&mut self, pressed: bool, tool_index: i32) {
         if !pressed {
             return;
         }
-        self.mode = match tool_index {
-            0 => TerrainToolMode::Height,
-            1 => TerrainToolMode::Level,
-            2 => TerrainToolMode::Smooth,
-            3 => TerrainToolMode::Bridge,
-            4 => {
-                // Only reset when genuinely switching to the tool, not on
-                // re-clicks (which the gui_input handler already handled).
-                if self.mode != TerrainToolMode::GrassMask {
-                    self.should_mask_grass = false;
-                    self.update_grass_mask_button_text();
-                }
-                TerrainToolMode::GrassMask
-            }
-            5 => TerrainToolMode::VertexPaint,
-            6 => TerrainToolMode::DebugBrush,
-            7 => TerrainToolMode::ChunkManagement,
-            _ => TerrainToolMode::Height,
-        };
+        let new_mode = TOOL_MODES
+            .get(tool_index as usize)
+            .copied()
+            .unwrap_or_default();
+        // Only reset when genuinely switching to the grass tool, not on
+        // re-clicks (which the gui_input handler already handled).
+        if new_mode == TerrainToolMode::GrassMask && self.mode != TerrainToolMode::GrassMask {
+            self.should_mask_grass = false;
+            self.update_grass_mask_button_text();
+        }
+        self.mode = new_mode;
         // Use call_deferred to avoid borrow conflict from signal dispatch
         self.base_mut()
             .call_deferred("_rebuild_attributes_deferred", &[]);
@@ -938,7 +1573,7 @@ impl PixyTerrainPlugin {
     fn on_grass_mask_button_down(&mut self) {
         // button_down fires before toggle logic, so is_pressed() == true
         // means the button was already active — this is a re-click.
-        let Some(btn) = self.tool_buttons.get(4) else {
+        let Some(btn) = self.tool_buttons.get(grass_tool_index()) else {
             return;
         };
         if !btn.is_pressed() {
@@ -949,7 +1584,7 @@ impl PixyTerrainPlugin {
     }
 
     fn update_grass_mask_button_text(&mut self) {
-        if let Some(btn) = self.tool_buttons.get_mut(4) {
+        if let Some(btn) = self.tool_buttons.get_mut(grass_tool_index()) {
             let text = if self.should_mask_grass {
                 "Remove Grass"
             } else {
@@ -972,6 +1607,9 @@ impl PixyTerrainPlugin {
                     BrushType::Square
                 };
             }
+            "snap_surface" => {
+                self.snap_to_surface = value.to();
+            }
             "size" => {
                 let v = value.to::<f64>();
                 self.brush_size = v as f32;
@@ -986,6 +1624,54 @@ impl PixyTerrainPlugin {
                     Self::update_slider_label(hbox, "strength", "Strength", v);
                 }
             }
+            "sculpt_offset" => {
+                let v = value.to::<f64>();
+                self.sculpt_offset = v as f32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "sculpt_offset", "Raise", v);
+                }
+            }
+            "step_size" => {
+                let v = value.to::<f64>();
+                self.step_size = v as f32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "step_size", "Step", v);
+                }
+            }
+            "limit_min" => {
+                let v = value.to::<f64>();
+                self.limit_min = v as f32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "limit_min", "Min", v);
+                }
+            }
+            "limit_max" => {
+                let v = value.to::<f64>();
+                self.limit_max = v as f32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "limit_max", "Max", v);
+                }
+            }
+            "contour_depth" => {
+                let v = value.to::<f64>();
+                self.contour_depth = v as f32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "contour_depth", "Depth", v);
+                }
+            }
+            "edge_depth" => {
+                let v = value.to::<f64>();
+                self.edge_depth = v as i32;
+                if let Some(ref hbox) = self.attributes_hbox {
+                    Self::update_slider_label(hbox, "edge_depth", "Edge", v);
+                }
+            }
+            "sculpt_additive" => {
+                self.sculpt_additive = value.to();
+            }
+            "level_average" => {
+                self.level_average = value.to();
+            }
             "height" => {
                 let v = value.to::<f64>();
                 self.height = v as f32;
@@ -1013,6 +1699,12 @@ impl PixyTerrainPlugin {
             "paint_walls" => {
                 self.paint_walls_mode = value.to();
             }
+            "erase_paint" => {
+                self.erase_paint_mode = value.to();
+            }
+            "paint_with_height" => {
+                self.paint_with_height = value.to();
+            }
             "quick_paint" => {
                 let idx: i64 = value.to();
                 if idx == 0 {
@@ -1067,6 +1759,12 @@ impl PixyTerrainPlugin {
         }
     }
 
+    /// Called when the brush falloff curve is picked or cleared in the attributes bar.
+    #[func]
+    fn on_falloff_curve_changed(&mut self, resource: Variant) {
+        self.falloff_curve = resource.try_to::<Gd<Curve>>().ok();
+    }
+
     /// Called when a texture resource is changed via EditorResourcePicker.
     /// Godot passes signal args first (resource), then bound args (setting_name).
     #[func]
@@ -1108,6 +1806,14 @@ impl PixyTerrainPlugin {
 // =======================================
 
 impl PixyTerrainPlugin {
+    /// The terrain being edited, if it is still alive.
+    fn selected_terrain(&self) -> Option<Gd<PixyTerrain>> {
+        self.current_terrain
+            .as_ref()
+            .filter(|node| node.is_instance_valid())
+            .and_then(|node| node.clone().try_cast::<PixyTerrain>().ok())
+    }
+
     /// Build a GizmoState snapshot from current brush state.
     pub fn get_gizmo_state(&self) -> GizmoState {
         GizmoState {
@@ -1122,6 +1828,10 @@ impl PixyTerrainPlugin {
             is_setting: self.is_setting,
             draw_height_set: self.draw_height_set,
             is_drawing: self.is_drawing,
+            ctrl_held: Input::singleton().is_key_pressed(godot::global::Key::CTRL),
+            is_making_bridge: self.is_making_bridge,
+            bridge_start: self.bridge_start_pos,
+            ease_value: self.ease_value,
         }
     }
 
@@ -1287,6 +1997,38 @@ impl PixyTerrainPlugin {
         hbox.add_child(&center);
     }
 
+    /// Falloff checkbox plus a `Curve` picker that overrides the smoothstep falloff shape.
+    fn add_falloff_attributes(&mut self, plugin_ref: &Gd<PixyTerrainPlugin>) {
+        self.add_checkbox_attribute("falloff", "Falloff", self.falloff, plugin_ref);
+
+        let Some(ref mut hbox) = self.attributes_hbox else {
+            return;
+        };
+
+        let mut center = CenterContainer::new_alloc();
+        center.set_custom_minimum_size(Vector2::new(140.0, 42.0));
+
+        let mut vbox = VBoxContainer::new_alloc();
+        vbox.add_theme_constant_override("separation", 0);
+
+        let mut label = Label::new_alloc();
+        label.set_text("Falloff Curve");
+
+        let mut picker = EditorResourcePicker::new_alloc();
+        picker.set_base_type("Curve");
+        if let Some(ref curve) = self.falloff_curve {
+            picker.set_edited_resource(curve);
+        }
+        picker.set_custom_minimum_size(Vector2::new(140.0, 24.0));
+        let callable = Callable::from_object_method(plugin_ref, "on_falloff_curve_changed");
+        picker.connect("resource_changed", &callable);
+
+        vbox.add_child(&label);
+        vbox.add_child(&picker);
+        center.add_child(&vbox);
+        hbox.add_child(&center);
+    }
+
     fn add_option_attribute(
         &mut self,
         name: &str,
@@ -1444,11 +2186,29 @@ impl PixyTerrainPlugin {
             self.brush_size as f64,
             plugin_ref,
         );
+        self.add_checkbox_attribute("snap_surface", "Snap", self.snap_to_surface, plugin_ref);
+        if blends_into_neighbors(self.mode) {
+            self.add_slider_attribute(
+                "edge_depth",
+                "Edge",
+                1.0,
+                MAX_EDGE_DEPTH as f64,
+                1.0,
+                self.edge_depth as f64,
+                plugin_ref,
+            );
+        }
     }
 
     fn add_paint_section(&mut self, plugin_ref: &Gd<PixyTerrainPlugin>) {
         self.add_group_separator("Paint");
         self.add_quick_paint_dropdown(plugin_ref);
+        self.add_checkbox_attribute(
+            "paint_with_height",
+            "Texture",
+            self.paint_with_height,
+            plugin_ref,
+        );
     }
 
     fn rebuild_attributes_impl(&mut self, plugin_ref: Gd<PixyTerrainPlugin>) {
@@ -1467,7 +2227,7 @@ impl PixyTerrainPlugin {
             TerrainToolMode::Height => {
                 self.add_common_brush_attributes(&plugin_ref);
                 self.add_checkbox_attribute("flatten", "Flatten", self.flatten, &plugin_ref);
-                self.add_checkbox_attribute("falloff", "Falloff", self.falloff, &plugin_ref);
+                self.add_falloff_attributes(&plugin_ref);
                 self.add_paint_section(&plugin_ref);
             }
             TerrainToolMode::Level => {
@@ -1481,39 +2241,126 @@ impl PixyTerrainPlugin {
                     self.height as f64,
                     &plugin_ref,
                 );
-                self.add_checkbox_attribute("falloff", "Falloff", self.falloff, &plugin_ref);
+                self.add_checkbox_attribute(
+                    "level_average",
+                    "Average",
+                    self.level_average,
+                    &plugin_ref,
+                );
+                self.add_falloff_attributes(&plugin_ref);
                 self.add_paint_section(&plugin_ref);
             }
-            TerrainToolMode::Smooth => {
+            TerrainToolMode::Contour => {
                 self.add_common_brush_attributes(&plugin_ref);
                 self.add_slider_attribute(
-                    "strength",
-                    "Strength",
-                    0.1,
-                    10.0,
-                    0.1,
-                    self.strength as f64,
+                    "contour_depth",
+                    "Depth",
+                    0.5,
+                    20.0,
+                    0.5,
+                    self.contour_depth as f64,
                     &plugin_ref,
                 );
+                self.add_falloff_attributes(&plugin_ref);
                 self.add_paint_section(&plugin_ref);
             }
-            TerrainToolMode::Bridge => {
+            TerrainToolMode::Step => {
                 self.add_common_brush_attributes(&plugin_ref);
                 self.add_slider_attribute(
-                    "ease_value",
-                    "Ease",
-                    -5.0,
-                    5.0,
+                    "step_size",
+                    "Step",
                     0.1,
-                    self.ease_value as f64,
+                    10.0,
+                    0.1,
+                    self.step_size as f64,
                     &plugin_ref,
                 );
                 self.add_paint_section(&plugin_ref);
             }
-            TerrainToolMode::GrassMask => {
-                self.add_common_brush_attributes(&plugin_ref);
-            }
-            TerrainToolMode::VertexPaint => {
+            TerrainToolMode::Smooth | TerrainToolMode::Sharpen => {
+                self.add_common_brush_attributes(&plugin_ref);
+                self.add_slider_attribute(
+                    "strength",
+                    "Strength",
+                    0.1,
+                    10.0,
+                    0.1,
+                    self.strength as f64,
+                    &plugin_ref,
+                );
+                self.add_paint_section(&plugin_ref);
+            }
+            TerrainToolMode::Sculpt => {
+                self.add_common_brush_attributes(&plugin_ref);
+                self.add_slider_attribute(
+                    "strength",
+                    "Strength",
+                    0.1,
+                    10.0,
+                    0.1,
+                    self.strength as f64,
+                    &plugin_ref,
+                );
+                self.add_slider_attribute(
+                    "sculpt_offset",
+                    "Raise",
+                    0.5,
+                    20.0,
+                    0.5,
+                    self.sculpt_offset as f64,
+                    &plugin_ref,
+                );
+                self.add_checkbox_attribute(
+                    "sculpt_additive",
+                    "Additive",
+                    self.sculpt_additive,
+                    &plugin_ref,
+                );
+                self.add_paint_section(&plugin_ref);
+            }
+            TerrainToolMode::Bridge => {
+                self.add_common_brush_attributes(&plugin_ref);
+                self.add_slider_attribute(
+                    "ease_value",
+                    "Ease",
+                    -5.0,
+                    5.0,
+                    0.1,
+                    self.ease_value as f64,
+                    &plugin_ref,
+                );
+                self.add_paint_section(&plugin_ref);
+            }
+            TerrainToolMode::Clone => {
+                self.add_common_brush_attributes(&plugin_ref);
+                self.add_falloff_attributes(&plugin_ref);
+                self.add_paint_section(&plugin_ref);
+            }
+            TerrainToolMode::PaintLimits => {
+                self.add_common_brush_attributes(&plugin_ref);
+                self.add_slider_attribute(
+                    "limit_min",
+                    "Min",
+                    -50.0,
+                    50.0,
+                    0.1,
+                    self.limit_min as f64,
+                    &plugin_ref,
+                );
+                self.add_slider_attribute(
+                    "limit_max",
+                    "Max",
+                    -50.0,
+                    50.0,
+                    0.1,
+                    self.limit_max as f64,
+                    &plugin_ref,
+                );
+            }
+            TerrainToolMode::GrassMask => {
+                self.add_common_brush_attributes(&plugin_ref);
+            }
+            TerrainToolMode::VertexPaint => {
                 self.add_common_brush_attributes(&plugin_ref);
                 let mat_options: Vec<&str> = (0..15)
                     .map(|i| match i {
@@ -1548,6 +2395,12 @@ impl PixyTerrainPlugin {
                     self.paint_walls_mode,
                     &plugin_ref,
                 );
+                self.add_checkbox_attribute(
+                    "erase_paint",
+                    "Erase",
+                    self.erase_paint_mode,
+                    &plugin_ref,
+                );
             }
             TerrainToolMode::DebugBrush => {
                 self.add_common_brush_attributes(&plugin_ref);
@@ -1733,6 +2586,7 @@ impl PixyTerrainPlugin {
             scale_slider.set_max(40.0);
             scale_slider.set_step(0.1);
             scale_slider.set_value(scales[(slot - 1) as usize] as f64);
+            scale_slider.set_name(&scale_name);
             scale_slider.set_custom_minimum_size(Vector2::new(180.0, 0.0));
 
             let callable = Callable::from_object_method(&plugin_ref, "on_attribute_changed")
@@ -1750,6 +2604,7 @@ impl PixyTerrainPlugin {
 
                 let mut color_picker = ColorPickerButton::new_alloc();
                 color_picker.set_pick_color(ground_colors[(slot - 1) as usize]);
+                color_picker.set_name(&color_name);
                 color_picker.set_custom_minimum_size(Vector2::new(180.0, 28.0));
 
                 let callable = Callable::from_object_method(&plugin_ref, "on_attribute_changed")
@@ -1776,6 +2631,7 @@ impl PixyTerrainPlugin {
                 let mut grass_cb = CheckBox::new_alloc();
                 grass_cb.set_text("Has Grass");
                 grass_cb.set_pressed(has_grass[(slot - 1) as usize]);
+                grass_cb.set_name(&grass_name);
 
                 let callable = Callable::from_object_method(&plugin_ref, "on_attribute_changed")
                     .bindv(&varray![grass_name.to_variant()]);
@@ -1908,6 +2764,7 @@ impl PixyTerrainPlugin {
                         }
 
                         let sample = if self.falloff {
+                            let curve = self.falloff_curve.as_ref();
                             let t = match self.brush_type {
                                 BrushType::Round => {
                                     ((max_distance - dist_sq) / max_distance).clamp(0.0, 1.0)
@@ -1921,8 +2778,7 @@ impl PixyTerrainPlugin {
                                     1.0 - d.clamp(0.2, 1.0)
                                 }
                             };
-                            let t = t.clamp(0.001, 0.999);
-                            t * t * (3.0 - 2.0 * t)
+                            falloff_weight(t, curve.map(|c| move |x: f32| c.sample(x)))
                         } else {
                             1.0
                         };
@@ -1942,6 +2798,13 @@ impl PixyTerrainPlugin {
                 }
             }
         }
+
+        if let Some(selection) = terrain.bind().selection() {
+            retain_selected(&mut self.current_draw_pattern, selection, dim);
+        }
+        if let Some(region) = terrain.bind().edit_region() {
+            retain_in_region(&mut self.current_draw_pattern, region, dim, cell_size);
+        }
     }
 
     #[allow(clippy::type_complexity)]
@@ -1949,6 +2812,10 @@ impl PixyTerrainPlugin {
         if self.current_draw_pattern.is_empty() {
             return;
         }
+        if self.mode == TerrainToolMode::PaintLimits {
+            self.draw_limit_pattern(terrain, dim);
+            return;
+        }
 
         // Snapshot the pattern (avoid borrow issues)
         let pattern_snapshot: Vec<([i32; 2], Vec<([i32; 2], f32)>)> = self
@@ -1973,25 +2840,28 @@ impl PixyTerrainPlugin {
 
         let mut first_chunk: Option<[i32; 2]> = None;
 
-        // Compute global average for smooth mode
-        let global_avg_height = if self.mode == TerrainToolMode::Smooth {
-            let mut sum = 0.0f32;
-            let mut count = 0usize;
+        // Compute global average for smooth mode and averaged level
+        let global_avg_height = if self.mode == TerrainToolMode::Smooth
+            || (self.mode == TerrainToolMode::Level && self.level_average)
+        {
+            let mut heights = Vec::new();
             for (chunk_key, cells) in &pattern_snapshot {
                 if let Some(chunk) = terrain.bind().get_chunk(chunk_key[0], chunk_key[1]) {
                     let c = chunk.bind();
                     for &(cell_key, _) in cells {
-                        sum += c.get_height(Vector2i::new(cell_key[0], cell_key[1]));
-                        count += 1;
+                        heights.push(c.get_height(Vector2i::new(cell_key[0], cell_key[1])));
                     }
                 }
             }
-            sum / count.max(1) as f32
+            mean_height(&heights)
         } else {
             0.0
         };
 
-        for (chunk_key, cells) in &pattern_snapshot {
+        let stroke = stroke_cells(&pattern_snapshot, |key| {
+            terrain.bind().has_chunk(key[0], key[1])
+        });
+        for (chunk_key, cells) in stroke {
             if first_chunk.is_none() {
                 first_chunk = Some(*chunk_key);
             }
@@ -2019,6 +2889,65 @@ impl PixyTerrainPlugin {
                     undo_height.set(chunk_coords, undo_chunk);
                 }
 
+                TerrainToolMode::Sharpen => {
+                    let mut do_chunk = VarDictionary::new();
+                    let mut undo_chunk = VarDictionary::new();
+
+                    for &(cell_key, sample) in cells {
+                        let sample = sample.clamp(0.001, 0.999);
+                        let cell_coords = Vector2i::new(cell_key[0], cell_key[1]);
+                        let c = chunk.bind();
+                        let old_h = c.get_height(cell_coords);
+
+                        // 4-neighbor average (same sampling footprint as the marching squares cell)
+                        let mut lo = old_h;
+                        let mut hi = old_h;
+                        let mut sum = 0.0f32;
+                        let mut count = 0usize;
+                        for (dx, dz) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                            if let Some(h) = c.get_height_at(cell_key[0] + dx, cell_key[1] + dz) {
+                                sum += h;
+                                count += 1;
+                                lo = lo.min(h);
+                                hi = hi.max(h);
+                            }
+                        }
+                        if count == 0 {
+                            continue;
+                        }
+                        let neighbor_avg = sum / count as f32;
+                        let new_h =
+                            sharpen_height(old_h, neighbor_avg, sample * self.strength, hi - lo);
+                        do_chunk.set(cell_coords, new_h);
+                        undo_chunk.set(cell_coords, old_h);
+                    }
+
+                    do_height.set(chunk_coords, do_chunk);
+                    undo_height.set(chunk_coords, undo_chunk);
+                }
+
+                TerrainToolMode::Sculpt => {
+                    let mut do_chunk = VarDictionary::new();
+                    let mut undo_chunk = VarDictionary::new();
+
+                    for &(cell_key, sample) in cells {
+                        let sample = sample.clamp(0.001, 0.999);
+                        let cell_coords = Vector2i::new(cell_key[0], cell_key[1]);
+                        let old_h = chunk.bind().get_height(cell_coords);
+                        let step = sample * self.strength;
+                        let new_h = if self.sculpt_additive {
+                            sculpt_add(old_h, step, self.sculpt_lower)
+                        } else {
+                            sculpt_step(old_h, self.sculpt_target, step)
+                        };
+                        do_chunk.set(cell_coords, new_h);
+                        undo_chunk.set(cell_coords, old_h);
+                    }
+
+                    do_height.set(chunk_coords, do_chunk);
+                    undo_height.set(chunk_coords, undo_chunk);
+                }
+
                 TerrainToolMode::DebugBrush => {
                     for &(cell_key, _) in cells {
                         let c = chunk.bind();
@@ -2027,14 +2956,15 @@ impl PixyTerrainPlugin {
                         let col0 = c.get_color_0(cell_key[0], cell_key[1]);
                         let col1 = c.get_color_1(cell_key[0], cell_key[1]);
                         godot_print!(
-                            "DEBUG: chunk ({},{}), cell ({},{}), h={:.3}, c0={:?}, c1={:?}",
+                            "DEBUG: chunk ({},{}), cell ({},{}), h={:.3}, c0={:?}, c1={:?}, tex={}",
                             chunk_key[0],
                             chunk_key[1],
                             cell_key[0],
                             cell_key[1],
                             h,
                             col0,
-                            col1
+                            col1,
+                            marching_squares::colors_to_texture_index(col0, col1)
                         );
                     }
                     continue;
@@ -2063,8 +2993,33 @@ impl PixyTerrainPlugin {
                             }
 
                             TerrainToolMode::Level => {
+                                let target = if self.level_average {
+                                    global_avg_height
+                                } else {
+                                    self.height
+                                };
+                                let old_h = chunk.bind().get_height(cell_coords);
+                                let new_h = lerp_f32(old_h, target, sample);
+                                do_chunk.set(cell_coords, new_h);
+                                undo_chunk.set(cell_coords, old_h);
+                            }
+
+                            TerrainToolMode::Contour => {
                                 let old_h = chunk.bind().get_height(cell_coords);
-                                let new_h = lerp_f32(old_h, self.height, sample);
+                                let new_h = contour_height(
+                                    old_h,
+                                    self.contour_depth,
+                                    sample,
+                                    self.contour_raise,
+                                );
+                                do_chunk.set(cell_coords, new_h);
+                                undo_chunk.set(cell_coords, old_h);
+                            }
+
+                            TerrainToolMode::Step => {
+                                // Every footprint cell moves a whole step; falloff would break the grid.
+                                let old_h = chunk.bind().get_height(cell_coords);
+                                let new_h = step_height(old_h, self.step_size, self.step_lower);
                                 do_chunk.set(cell_coords, new_h);
                                 undo_chunk.set(cell_coords, old_h);
                             }
@@ -2101,16 +3056,11 @@ impl PixyTerrainPlugin {
                                 let bridge_dir = (b_end - b_start) / bridge_length;
                                 let cell_vec = global_cell - b_start;
                                 let linear_offset = cell_vec.dot(bridge_dir);
-                                let mut progress = (linear_offset / bridge_length).clamp(0.0, 1.0);
-
-                                if self.ease_value != -1.0 {
-                                    progress = godot_ease(progress, self.ease_value);
-                                }
-
-                                let bridge_height = lerp_f32(
+                                let bridge_height = bridge_height(
                                     self.bridge_start_pos.y,
                                     self.brush_position.y,
-                                    progress,
+                                    linear_offset / bridge_length,
+                                    self.ease_value,
                                 );
 
                                 let old_h = chunk.bind().get_height(cell_coords);
@@ -2119,25 +3069,60 @@ impl PixyTerrainPlugin {
                             }
 
                             TerrainToolMode::VertexPaint => {
+                                let (new_c0, new_c1) = vertex_paint_colors(
+                                    self.erase_paint_mode,
+                                    (self.vertex_color_0, self.vertex_color_1),
+                                );
                                 if self.paint_walls_mode {
                                     let old_c0 =
                                         chunk.bind().get_wall_color_0(cell_key[0], cell_key[1]);
                                     let old_c1 =
                                         chunk.bind().get_wall_color_1(cell_key[0], cell_key[1]);
-                                    do_chunk.set(cell_coords, self.vertex_color_0);
+                                    do_chunk.set(cell_coords, new_c0);
                                     undo_chunk.set(cell_coords, old_c0);
-                                    do_chunk_cc.set(cell_coords, self.vertex_color_1);
+                                    do_chunk_cc.set(cell_coords, new_c1);
                                     undo_chunk_cc.set(cell_coords, old_c1);
                                 } else {
                                     let old_c0 = chunk.bind().get_color_0(cell_key[0], cell_key[1]);
                                     let old_c1 = chunk.bind().get_color_1(cell_key[0], cell_key[1]);
-                                    do_chunk.set(cell_coords, self.vertex_color_0);
+                                    do_chunk.set(cell_coords, new_c0);
                                     undo_chunk.set(cell_coords, old_c0);
-                                    do_chunk_cc.set(cell_coords, self.vertex_color_1);
+                                    do_chunk_cc.set(cell_coords, new_c1);
                                     undo_chunk_cc.set(cell_coords, old_c1);
                                 }
                             }
 
+                            TerrainToolMode::Clone => {
+                                let Some(source) = self.clone_source else {
+                                    continue;
+                                };
+                                let cell_world =
+                                    vertex_position(chunk_key, cell_key, dim, cell_size);
+                                let src_world = clone_source_position(
+                                    Vector2::new(source.x, source.z),
+                                    Vector2::new(
+                                        self.clone_stroke_origin.x,
+                                        self.clone_stroke_origin.z,
+                                    ),
+                                    cell_world,
+                                );
+                                let (src_chunk, src_cell) =
+                                    world_to_chunk_cell(src_world, dim, cell_size);
+                                let Some(src_chunk_gd) =
+                                    terrain.bind().get_chunk(src_chunk[0], src_chunk[1])
+                                else {
+                                    continue;
+                                };
+                                let src_h = src_chunk_gd
+                                    .bind()
+                                    .get_height(Vector2i::new(src_cell[0], src_cell[1]));
+
+                                let old_h = chunk.bind().get_height(cell_coords);
+                                let new_h = lerp_f32(old_h, src_h, sample);
+                                do_chunk.set(cell_coords, new_h);
+                                undo_chunk.set(cell_coords, old_h);
+                            }
+
                             // Height tool (default)
                             _ => {
                                 let old_h = chunk.bind().get_height(cell_coords);
@@ -2246,11 +3231,47 @@ impl PixyTerrainPlugin {
             }
         }
 
+        // Phase 1.6: Combined stroke -- paint the Vertex Paint material under the height edit
+        if paints_texture_with_height(
+            self.mode,
+            self.paint_with_height,
+            self.current_quick_paint.is_some(),
+        ) {
+            let paint = (self.vertex_color_0, self.vertex_color_1);
+            let colors = combined_stroke_colors(&pattern_snapshot, paint, |chunk, [x, z]| {
+                let chunk = terrain.bind().get_chunk(chunk[0], chunk[1])?;
+                let c = chunk.bind();
+                Some((c.get_color_0(x, z), c.get_color_1(x, z)))
+            });
+
+            for (chunk_key, cells) in colors {
+                let chunk_coords = Vector2i::new(chunk_key[0], chunk_key[1]);
+                let mut do_gc0_chunk = VarDictionary::new();
+                let mut undo_gc0_chunk = VarDictionary::new();
+                let mut do_gc1_chunk = VarDictionary::new();
+                let mut undo_gc1_chunk = VarDictionary::new();
+
+                for (cell_key, ((do_c0, do_c1), (undo_c0, undo_c1))) in cells {
+                    let cell = Vector2i::new(cell_key[0], cell_key[1]);
+                    undo_gc0_chunk.set(cell, undo_c0);
+                    undo_gc1_chunk.set(cell, undo_c1);
+                    do_gc0_chunk.set(cell, do_c0);
+                    do_gc1_chunk.set(cell, do_c1);
+                }
+
+                do_color_0.set(chunk_coords, do_gc0_chunk);
+                undo_color_0.set(chunk_coords, undo_gc0_chunk);
+                do_color_1.set(chunk_coords, do_gc1_chunk);
+                undo_color_1.set(chunk_coords, undo_gc1_chunk);
+            }
+        }
+
         // Phase 2: Cross-chunk edge propagation
         self.propagate_cross_chunk_edges(
             terrain,
             &pattern_snapshot,
             dim,
+            cell_size,
             &mut do_height,
             &mut undo_height,
             &mut do_color_0,
@@ -2265,16 +3286,11 @@ impl PixyTerrainPlugin {
             &mut undo_grass_mask,
         );
 
+        // Height limits clamp the do side only, so undo restores out-of-limit heights exactly
+        terrain.bind().limit_pattern_heights(&do_height);
+
         // Phase 3: Wall color expansion for height modes
-        if self.current_quick_paint.is_none()
-            && matches!(
-                self.mode,
-                TerrainToolMode::Height
-                    | TerrainToolMode::Level
-                    | TerrainToolMode::Smooth
-                    | TerrainToolMode::Bridge
-            )
-        {
+        if self.current_quick_paint.is_none() && is_height_mode(self.mode) {
             self.expand_wall_colors(
                 terrain,
                 dim,
@@ -2321,17 +3337,21 @@ impl PixyTerrainPlugin {
 
         let action_name = match self.mode {
             TerrainToolMode::Height => "terrain height",
+            TerrainToolMode::Sculpt => "terrain sculpt",
             TerrainToolMode::Level => "terrain level",
+            TerrainToolMode::Contour => "terrain contour",
+            TerrainToolMode::Step => "terrain step",
             TerrainToolMode::Smooth => "terrain smooth",
+            TerrainToolMode::Sharpen => "terrain sharpen",
             TerrainToolMode::Bridge => "terrain slope",
+            TerrainToolMode::Clone => "terrain clone",
             TerrainToolMode::GrassMask => "terrain grass mask",
-            TerrainToolMode::VertexPaint => {
-                if self.paint_walls_mode {
-                    "terrain wall paint"
-                } else {
-                    "terrain vertex paint"
-                }
-            }
+            TerrainToolMode::VertexPaint => match (self.paint_walls_mode, self.erase_paint_mode) {
+                (true, true) => "terrain wall paint erase",
+                (true, false) => "terrain wall paint",
+                (false, true) => "terrain vertex paint erase",
+                (false, false) => "terrain vertex paint",
+            },
             _ => "terrain draw",
         };
 
@@ -2339,12 +3359,42 @@ impl PixyTerrainPlugin {
         self.register_undo_redo(action_name, &terrain_node, do_patterns, undo_patterns);
     }
 
+    /// PaintLimits: set (or clear) the height limit of every vertex under the stroke as one
+    /// undo action. Limits are keyed by terrain-wide vertex, so no edge propagation is needed.
+    fn draw_limit_pattern(&mut self, terrain: &Gd<PixyTerrain>, dim: Vector3i) {
+        let limit = painted_limit(self.limit_min, self.limit_max, self.limit_clear);
+        let mut do_limits = VarDictionary::new();
+        let mut undo_limits = VarDictionary::new();
+        for [gx, gz] in footprint_vertices(&self.current_draw_pattern, dim) {
+            let vertex = Vector2i::new(gx, gz);
+            let old = terrain.bind().height_limits.get(vertex).unwrap_or_default();
+            do_limits.set(vertex, limit.map(|l| l.to_variant()).unwrap_or_default());
+            undo_limits.set(vertex, old);
+        }
+        if do_limits.is_empty() {
+            return;
+        }
+
+        let mut do_patterns = VarDictionary::new();
+        let mut undo_patterns = VarDictionary::new();
+        do_patterns.set("height_limit", do_limits);
+        undo_patterns.set("height_limit", undo_limits);
+        let action_name = if self.limit_clear {
+            "terrain clear limits"
+        } else {
+            "terrain paint limits"
+        };
+        let terrain_node: Gd<Node> = terrain.clone().upcast();
+        self.register_undo_redo(action_name, &terrain_node, do_patterns, undo_patterns);
+    }
+
     #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     fn propagate_cross_chunk_edges(
         &self,
         terrain: &Gd<PixyTerrain>,
         pattern_snapshot: &[([i32; 2], Vec<([i32; 2], f32)>)],
         dim: Vector3i,
+        cell_size: Vector2,
         do_height: &mut VarDictionary,
         undo_height: &mut VarDictionary,
         do_color_0: &mut VarDictionary,
@@ -2432,42 +3482,23 @@ impl PixyTerrainPlugin {
                             blend: 1.0,
                         });
 
-                        // Inner-cell blend for height modes
-                        if matches!(
-                            self.mode,
-                            TerrainToolMode::Height
-                                | TerrainToolMode::Level
-                                | TerrainToolMode::Smooth
-                                | TerrainToolMode::Bridge
-                        ) {
-                            let inner_x = if cx == -1 {
-                                x - 1
-                            } else if cx == 1 {
-                                x + 1
-                            } else {
-                                x
-                            };
-                            let inner_z = if cz == -1 {
-                                z - 1
-                            } else if cz == 1 {
-                                z + 1
-                            } else {
-                                z
-                            };
-
-                            if inner_x >= 0 && inner_x < dim.x && inner_z >= 0 && inner_z < dim.z {
+                        // Inner-cell blend for height modes (not Step, which stays on its grid)
+                        if blends_into_neighbors(self.mode) {
+                            for (inner, blend) in
+                                edge_inner_cells([x, z], [cx, cz], self.edge_depth, dim)
+                            {
                                 let already_in_pattern = self
                                     .current_draw_pattern
-                                    .get(&[adj_chunk[0], adj_chunk[1]])
-                                    .and_then(|cells| cells.get(&[inner_x, inner_z]))
+                                    .get(&adj_chunk)
+                                    .and_then(|cells| cells.get(&inner))
                                     .is_some();
                                 if !already_in_pattern {
                                     edges.push(EdgeEntry {
                                         src_chunk: Vector2i::new(chunk_key[0], chunk_key[1]),
                                         src_cell: Vector2i::new(cell_key[0], cell_key[1]),
                                         adj_chunk: Vector2i::new(adj_chunk[0], adj_chunk[1]),
-                                        adj_cell: Vector2i::new(inner_x, inner_z),
-                                        blend: 0.5,
+                                        adj_cell: Vector2i::new(inner[0], inner[1]),
+                                        blend,
                                     });
                                 }
                             }
@@ -2477,6 +3508,23 @@ impl PixyTerrainPlugin {
             }
         }
 
+        // Propagated cells (inner blend cells included) obey the selection and edit region
+        // just like the brush footprint
+        {
+            let t = terrain.bind();
+            let (selection, region) = (t.selection(), t.edit_region());
+            edges.retain(|edge| {
+                is_editable(
+                    [edge.adj_chunk.x, edge.adj_chunk.y],
+                    [edge.adj_cell.x, edge.adj_cell.y],
+                    selection,
+                    region,
+                    dim,
+                    cell_size,
+                )
+            });
+        }
+
         // Pass 2: Apply collected edges
         for edge in &edges {
             let adj_chunk_gd = terrain.bind().get_chunk(edge.adj_chunk.x, edge.adj_chunk.y);
@@ -2814,6 +3862,8 @@ impl PixyTerrainPlugin {
             .call_deferred("apply_collision_visibility_deferred", &[]);
     }
 
+    /// Change one texture-panel setting through the editor's undo history. Consecutive edits
+    /// of the same setting (a slider drag) merge into one undo step.
     fn apply_terrain_setting(&mut self, name: &str, value: &Variant) {
         let Some(ref terrain_node) = self.current_terrain else {
             return;
@@ -2821,42 +3871,520 @@ impl PixyTerrainPlugin {
         if !terrain_node.is_instance_valid() {
             return;
         }
-        let mut terrain: Gd<PixyTerrain> = terrain_node.clone().cast();
-
-        {
-            let mut t = terrain.bind_mut();
-            match name {
-                _ if name.starts_with("tex_scale_") => {
-                    let slot = name["tex_scale_".len()..].parse::<usize>().unwrap_or(1) - 1;
-                    let v = value.to::<f64>() as f32;
-                    if slot < t.texture_scales.len() {
-                        t.texture_scales[slot] = v;
-                    }
-                }
-                _ if name.starts_with("tex_has_grass_") => {
-                    let slot: usize = name["tex_has_grass_".len()..].parse().unwrap_or(2);
-                    let v: bool = value.to();
-                    match slot {
-                        2 => t.tex2_has_grass = v,
-                        3 => t.tex3_has_grass = v,
-                        4 => t.tex4_has_grass = v,
-                        5 => t.tex5_has_grass = v,
-                        6 => t.tex6_has_grass = v,
-                        _ => {}
-                    }
+        let Some(setting) = TerrainSetting::parse(name) else {
+            return;
+        };
+        let terrain: Gd<PixyTerrain> = terrain_node.clone().cast();
+        let property = setting.property();
+
+        // Capture the whole exported property before and after the change
+        let old_value = terrain.get(property.as_str());
+        let new_value = match setting {
+            TerrainSetting::TextureScale(slot) => {
+                let mut scales: PackedFloat32Array = old_value.to();
+                if slot >= scales.len() {
+                    return;
                 }
-                _ if name.starts_with("ground_color_") => {
-                    let slot = name["ground_color_".len()..].parse::<usize>().unwrap_or(1) - 1;
-                    let v: Color = value.to();
-                    if slot < t.ground_colors.len() {
-                        t.ground_colors[slot] = v;
-                    }
+                scales[slot] = value.to::<f64>() as f32;
+                scales.to_variant()
+            }
+            TerrainSetting::HasGrass(_) => value.to::<bool>().to_variant(),
+            TerrainSetting::GroundColor(slot) => {
+                let mut colors: PackedColorArray = old_value.to();
+                if slot >= colors.len() {
+                    return;
                 }
-                _ => {}
+                colors[slot] = value.to();
+                colors.to_variant()
             }
+        };
+        if new_value == old_value {
+            return;
         }
 
-        terrain.bind_mut().force_batch_update();
-        terrain.bind_mut().force_grass_material_update();
+        let plugin_ref = self.to_gd();
+        let Some(mut undo_redo) = self.base_mut().get_undo_redo() else {
+            godot_warn!("No EditorUndoRedoManager available");
+            return;
+        };
+        undo_redo
+            .create_action_ex(&format!("terrain setting {name}"))
+            .merge_mode(MergeMode::ENDS)
+            .done();
+        undo_redo.add_do_property(&terrain, property.as_str(), &new_value);
+        undo_redo.add_undo_property(&terrain, property.as_str(), &old_value);
+        for method in ["force_batch_update", "force_grass_material_update"] {
+            undo_redo.add_do_method(&terrain, method, &[]);
+            undo_redo.add_undo_method(&terrain, method, &[]);
+        }
+        // Refresh just this widget (no panel rebuild, which would drop a slider mid-drag)
+        let sync_args = [name.to_string().to_variant()];
+        undo_redo.add_do_method(&plugin_ref, "_sync_texture_setting_widget", &sync_args);
+        undo_redo.add_undo_method(&plugin_ref, "_sync_texture_setting_widget", &sync_args);
+        undo_redo.commit_action();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMS: Vector3i = Vector3i::new(33, 32, 33);
+    const CELL: Vector2 = Vector2::new(2.0, 2.0);
+
+    #[test]
+    fn test_sharpen_accentuates_ridge() {
+        // Ridge row: neighbors at 0, ridge at 2
+        let heights = [0.0f32, 2.0, 0.0];
+        let ridge_avg = (heights[0] + heights[2]) / 2.0;
+        let ridge = sharpen_height(heights[1], ridge_avg, 0.5, 2.0);
+        assert!(ridge - heights[0] > heights[1] - heights[0]);
+        assert_eq!(ridge, 3.0);
+    }
+
+    #[test]
+    fn test_sharpen_leaves_flat_region_unchanged() {
+        assert_eq!(sharpen_height(1.5, 1.5, 5.0, 0.0), 1.5);
+    }
+
+    #[test]
+    fn test_sharpen_is_clamped() {
+        assert_eq!(sharpen_height(2.0, 0.0, 100.0, 2.0), 4.0);
+        assert_eq!(sharpen_height(-2.0, 0.0, 100.0, 2.0), -4.0);
+    }
+
+    #[test]
+    fn test_clone_copies_raised_source_heights() {
+        // Source: a 3x3 raised block on an otherwise flat single chunk.
+        let mut heights = vec![vec![0.0f32; DIMS.x as usize]; DIMS.z as usize];
+        for z in 4..=6 {
+            for x in 4..=6 {
+                heights[z][x] = 5.0;
+            }
+        }
+
+        let source = Vector2::new(10.0, 10.0); // cell (5, 5)
+        let stroke_origin = Vector2::new(40.0, 40.0); // cell (20, 20)
+
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                let dest = Vector2::new((20 + dx) as f32 * CELL.x, (20 + dz) as f32 * CELL.y);
+                let src = clone_source_position(source, stroke_origin, dest);
+                let (chunk, cell) = world_to_chunk_cell(src, DIMS, CELL);
+                assert_eq!(chunk, [0, 0]);
+                let cloned = heights[cell[1] as usize][cell[0] as usize];
+                assert_eq!(cloned, 5.0, "dest offset ({dx}, {dz})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_erase_paint_restores_default_texture() {
+        let painted = marching_squares::texture_index_to_colors(6);
+        assert_eq!(vertex_paint_colors(false, painted), painted);
+
+        let erased = vertex_paint_colors(true, painted);
+        assert_ne!(erased, painted);
+        let fresh = marching_squares::ColorMaps::new_default(9);
+        assert_eq!(erased, (fresh.color_0[0], fresh.color_1[0]));
+        assert_eq!(erased, marching_squares::texture_index_to_colors(0));
+    }
+
+    #[test]
+    fn test_snap_lands_on_nearest_vertex() {
+        // A hit anywhere within half a cell of a vertex snaps onto that vertex
+        for (hit, expected) in [
+            (Vector2::new(10.9, 21.2), Vector2::new(10.0, 22.0)),
+            (Vector2::new(63.5, 0.4), Vector2::new(64.0, 0.0)),
+            (Vector2::new(65.2, 3.1), Vector2::new(66.0, 4.0)),
+            (Vector2::new(-1.2, -0.6), Vector2::new(-2.0, 0.0)),
+        ] {
+            let (chunk, cell) = world_to_chunk_cell(hit, DIMS, CELL);
+            let snapped = vertex_position(chunk, cell, DIMS, CELL);
+            assert_eq!(snapped, expected, "hit {hit:?}");
+            // Snapping again is stable, even on a vertex shared by two chunks
+            let (chunk2, cell2) = world_to_chunk_cell(snapped, DIMS, CELL);
+            assert_eq!(vertex_position(chunk2, cell2, DIMS, CELL), snapped);
+        }
+    }
+
+    #[test]
+    fn test_selection_clips_brush_pattern() {
+        // Brush covering vertices x 30..=34 along z = 5, straddling the chunk 0/1 seam at x = 32
+        let mut pattern: HashMap<[i32; 2], HashMap<[i32; 2], f32>> = HashMap::new();
+        for x in 30..=32 {
+            pattern.entry([0, 0]).or_default().insert([x, 5], 1.0);
+        }
+        for x in 0..=2 {
+            pattern.entry([1, 0]).or_default().insert([x, 5], 1.0);
+        }
+
+        // Selection ends at the seam vertex
+        let selection: HashSet<[i32; 2]> = (28..=32).map(|x| [x, 5]).collect();
+        retain_selected(&mut pattern, &selection, DIMS);
+
+        assert_eq!(pattern.len(), 2);
+        let mut kept: Vec<i32> = pattern[&[0, 0]].keys().map(|c| c[0]).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, vec![30, 31, 32]);
+        // Chunk 1 only keeps its copy of the shared seam vertex
+        assert_eq!(pattern[&[1, 0]].keys().collect::<Vec<_>>(), vec![&[0, 5]]);
+
+        retain_selected(&mut pattern, &HashSet::new(), DIMS);
+        assert!(pattern.is_empty());
+    }
+
+    #[test]
+    fn test_edit_region_clips_brush_straddling_its_edge() {
+        // Brush over vertices x 28..=34 (world x 56..=68) along z = 5, across the chunk 0/1 seam
+        let mut pattern: HashMap<[i32; 2], HashMap<[i32; 2], f32>> = HashMap::new();
+        for x in 28..=32 {
+            pattern.entry([0, 0]).or_default().insert([x, 5], 1.0);
+        }
+        for x in 0..=2 {
+            pattern.entry([1, 0]).or_default().insert([x, 5], 1.0);
+        }
+
+        // Region ends at world x 61, between vertices 30 (x 60) and 31 (x 62)
+        let region = (Vector2::new(0.0, 0.0), Vector2::new(61.0, 100.0));
+        retain_in_region(&mut pattern, region, DIMS, CELL);
+
+        assert_eq!(pattern.len(), 1, "chunk 1 lies entirely outside the region");
+        let mut kept: Vec<i32> = pattern[&[0, 0]].keys().map(|c| c[0]).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, vec![28, 29, 30]);
+    }
+
+    #[test]
+    fn test_sculpt_approaches_target_without_overshoot() {
+        let target = 5.0;
+        let mut h = 0.0f32;
+        let mut previous_gap = target - h;
+        for _ in 0..20 {
+            h = sculpt_step(h, target, 0.7);
+            let gap = target - h;
+            assert!(gap >= 0.0, "overshot to {h}");
+            assert!(gap < previous_gap || gap == 0.0);
+            previous_gap = gap;
+        }
+        assert_eq!(h, target);
+
+        // Lowering works the same way from above
+        let mut h = 3.0f32;
+        for _ in 0..10 {
+            h = sculpt_step(h, -1.0, 1.5);
+            assert!(h >= -1.0);
+        }
+        assert_eq!(h, -1.0);
+    }
+
+    #[test]
+    fn test_additive_sculpt_accumulates_past_target() {
+        let target = 2.0;
+        let (mut absolute, mut additive) = (0.0f32, 0.0f32);
+        for _ in 0..3 {
+            absolute = sculpt_step(absolute, target, 1.5);
+            additive = sculpt_add(additive, 1.5, false);
+        }
+        // Absolute converges on the target, additive keeps building: two passes beat one
+        assert_eq!(absolute, target);
+        assert_eq!(additive, 4.5);
+        assert!(sculpt_add(sculpt_add(0.0, 1.5, false), 1.5, false) > sculpt_add(0.0, 1.5, false));
+        assert_eq!(sculpt_add(1.0, 1.5, true), -0.5);
+    }
+
+    #[test]
+    fn test_contour_keeps_constant_offset_on_slope() {
+        // Slope rising 0.5 per vertex; full-strength contour carve 3 units deep
+        let slope: Vec<f32> = (0..10).map(|x| x as f32 * 0.5).collect();
+        let carved: Vec<f32> = slope
+            .iter()
+            .map(|&h| contour_height(h, 3.0, 1.0, false))
+            .collect();
+        for (before, after) in slope.iter().zip(&carved) {
+            assert_eq!(before - after, 3.0);
+        }
+        // Still a slope, not a flat plane
+        assert!(carved.windows(2).all(|w| w[1] > w[0]));
+
+        assert_eq!(contour_height(1.0, 3.0, 1.0, true), 4.0);
+        assert_eq!(contour_height(1.0, 3.0, 0.5, false), -0.5);
+    }
+
+    #[test]
+    fn test_level_to_average_flattens_to_mean() {
+        let heights = [2.0, 6.0, 2.0, 6.0];
+        let target = mean_height(&heights);
+        assert_eq!(target, 4.0);
+        for h in heights {
+            assert_eq!(lerp_f32(h, target, 1.0), 4.0);
+        }
+        assert_eq!(mean_height(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_falloff_weight_default_and_linear_curve() {
+        let no_curve: Option<fn(f32) -> f32> = None;
+        for t in [0.1f32, 0.25, 0.5, 0.75, 0.9] {
+            assert_eq!(falloff_weight(t, no_curve), t * t * (3.0 - 2.0 * t));
+            assert_eq!(falloff_weight(t, Some(|x: f32| x)), t);
+        }
+        // Edges stay inside the existing (0.001, 0.999) clamp
+        assert_eq!(falloff_weight(0.0, Some(|x: f32| x)), 0.001);
+        assert_eq!(falloff_weight(1.0, Some(|x: f32| x)), 0.999);
+        // Curves overshooting [0, 1] are clamped
+        assert_eq!(falloff_weight(0.5, Some(|_: f32| 2.0)), 1.0);
+    }
+
+    #[test]
+    fn test_terrain_setting_parses_panel_names() {
+        assert_eq!(
+            TerrainSetting::parse("tex_scale_3"),
+            Some(TerrainSetting::TextureScale(2))
+        );
+        assert_eq!(
+            TerrainSetting::parse("tex_has_grass_4"),
+            Some(TerrainSetting::HasGrass(4))
+        );
+        assert_eq!(
+            TerrainSetting::parse("ground_color_1"),
+            Some(TerrainSetting::GroundColor(0))
+        );
+        assert_eq!(TerrainSetting::parse("tex_scale_0"), None);
+        assert_eq!(TerrainSetting::parse("tex_has_grass_1"), None);
+        assert_eq!(TerrainSetting::parse("brush_size"), None);
+
+        // Undo restores the whole exported property the value came from
+        assert_eq!(TerrainSetting::TextureScale(2).property(), "texture_scales");
+        assert_eq!(TerrainSetting::HasGrass(4).property(), "tex4_has_grass");
+        assert_eq!(TerrainSetting::GroundColor(0).property(), "ground_colors");
+    }
+
+    #[test]
+    fn test_can_add_chunk_requires_edge_neighbor() {
+        let existing = [[0, 0], [1, 0]];
+        let has = |x: i32, z: i32| existing.contains(&[x, z]);
+        assert!(can_add_chunk(2, 0, false, has));
+        assert!(can_add_chunk(0, -1, false, has));
+        // Diagonal and distant slots are rejected
+        assert!(!can_add_chunk(2, 1, false, has));
+        assert!(!can_add_chunk(5, 5, false, has));
+        // Anything goes on an empty terrain
+        assert!(can_add_chunk(5, 5, true, |_, _| false));
+    }
+
+    #[test]
+    fn test_edge_depth_reaches_further_into_neighbor() {
+        let dim = Vector3i::new(33, 64, 33);
+        // Brush straddles the +x border: the neighbor's shared column is x = 0.
+        let depth_2 = edge_inner_cells([0, 10], [1, 0], 2, dim);
+        let cells: Vec<[i32; 2]> = depth_2.iter().map(|&(c, _)| c).collect();
+        assert_eq!(cells, vec![[1, 10], [2, 10]]);
+        assert!(depth_2[0].1 > depth_2[1].1 && depth_2[1].1 > 0.0);
+
+        // Depth 1 keeps the old single half-weight inner cell.
+        assert_eq!(
+            edge_inner_cells([0, 10], [1, 0], 1, dim),
+            vec![([1, 10], 0.5)]
+        );
+
+        // Diagonal neighbor toward -x/-z walks in from the far corner and stops at the edge.
+        let corner = edge_inner_cells([32, 32], [-1, -1], 2, dim);
+        assert_eq!(corner[1].0, [30, 30]);
+        assert_eq!(
+            edge_inner_cells([0, 0], [0, 1], 40, Vector3i::new(4, 8, 4)).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_brush_footprint_lists_every_pattern_cell() {
+        let dim = Vector3i::new(5, 8, 5);
+        let cell_size = Vector2::new(2.0, 2.0);
+        // A round brush at the corner shared by chunks (0,0) and (1,0).
+        let mut pattern: HashMap<[i32; 2], HashMap<[i32; 2], f32>> = HashMap::new();
+        pattern.entry([0, 0]).or_default().extend([
+            ([4, 1], 0.5),
+            ([3, 2], 0.5),
+            ([4, 2], 1.0),
+            ([4, 3], 0.5),
+        ]);
+        pattern
+            .entry([1, 0])
+            .or_default()
+            .extend([([1, 2], 0.5), ([0, 2], 1.0)]);
+
+        let cells = footprint_cells(&pattern);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], ([0, 0], [4, 1], 0.5));
+        assert_eq!(cells.last().unwrap().0, [1, 0]);
+
+        // The shared border vertex lands on the same spot from either chunk.
+        let a = vertex_position([0, 0], [4, 2], dim, cell_size);
+        let b = vertex_position([1, 0], [0, 2], dim, cell_size);
+        assert_eq!(a, b);
+        assert_eq!(a, Vector2::new(8.0, 4.0));
+    }
+
+    #[test]
+    fn test_combined_stroke_paints_only_height_modes() {
+        for mode in [
+            TerrainToolMode::Height,
+            TerrainToolMode::Sculpt,
+            TerrainToolMode::Level,
+            TerrainToolMode::Bridge,
+        ] {
+            assert!(paints_texture_with_height(mode, true, false), "{mode:?}");
+            assert!(!paints_texture_with_height(mode, false, false), "{mode:?}");
+            // QuickPaint already writes the ground colors for the stroke.
+            assert!(!paints_texture_with_height(mode, true, true), "{mode:?}");
+        }
+        for mode in [
+            TerrainToolMode::VertexPaint,
+            TerrainToolMode::GrassMask,
+            TerrainToolMode::DebugBrush,
+        ] {
+            assert!(!paints_texture_with_height(mode, true, false), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_step_height_moves_one_step_and_stays_snapped() {
+        let step = 0.5;
+        let mut h = 0.0;
+        for i in 1..=4 {
+            h = step_height(h, step, false);
+            assert!((h - i as f32 * step).abs() < 1e-5, "click {i}: {h}");
+        }
+        for i in (0..4).rev() {
+            h = step_height(h, step, true);
+            assert!((h - i as f32 * step).abs() < 1e-5, "lower to {i}: {h}");
+        }
+
+        // Off-grid heights snap first, so the result is always a whole step.
+        let h = step_height(1.37, step, false);
+        assert!((h / step - (h / step).round()).abs() < 1e-5);
+        assert!((h - 2.0).abs() < 1e-5);
+        assert_eq!(step_height(1.37, 0.0, false), 1.37);
+    }
+
+    #[test]
+    fn test_painted_limit_orders_bounds_and_clears() {
+        assert_eq!(
+            painted_limit(-2.0, 5.0, false),
+            Some(Vector2::new(-2.0, 5.0))
+        );
+        assert_eq!(
+            painted_limit(5.0, -2.0, false),
+            Some(Vector2::new(-2.0, 5.0))
+        );
+        assert_eq!(painted_limit(-2.0, 5.0, true), None);
+    }
+
+    #[test]
+    fn test_footprint_vertices_count_seam_vertices_once() {
+        let dim = Vector3i::new(5, 8, 5);
+        let mut pattern: HashMap<[i32; 2], HashMap<[i32; 2], f32>> = HashMap::new();
+        pattern
+            .entry([0, 0])
+            .or_default()
+            .extend([([3, 2], 1.0), ([4, 2], 1.0)]);
+        pattern
+            .entry([1, 0])
+            .or_default()
+            .extend([([0, 2], 0.5), ([1, 2], 0.5)]);
+
+        assert_eq!(
+            footprint_vertices(&pattern, dim),
+            vec![[3, 2], [4, 2], [5, 2]]
+        );
+    }
+
+    #[test]
+    fn test_edge_inner_cells_respect_selection_and_region() {
+        let dim = Vector3i::new(33, 32, 33);
+        let cell_size = Vector2::new(2.0, 2.0);
+        // Stroke on chunk (0, 0)'s +X border, blending 4 cells into chunk (1, 0)
+        let inner = edge_inner_cells([0, 10], [1, 0], 4, dim);
+        assert_eq!(inner.len(), 4);
+
+        // Only the first two inner vertices (global x 33, 34) are selected
+        let selection: HashSet<[i32; 2]> = [[32, 10], [33, 10], [34, 10]].into_iter().collect();
+        let kept: Vec<[i32; 2]> = inner
+            .iter()
+            .map(|&(cell, _)| cell)
+            .filter(|&cell| is_editable([1, 0], cell, Some(&selection), None, dim, cell_size))
+            .collect();
+        assert_eq!(kept, vec![[1, 10], [2, 10]]);
+
+        // A region ending at x = 70 keeps global x 33..=35 (positions 66, 68, 70)
+        let region = (Vector2::new(0.0, 0.0), Vector2::new(70.0, 100.0));
+        let kept = inner
+            .iter()
+            .filter(|&&(cell, _)| is_editable([1, 0], cell, None, Some(region), dim, cell_size))
+            .count();
+        assert_eq!(kept, 3);
+        assert!(inner.iter().all(|&(cell, _)| is_editable(
+            [1, 0],
+            cell,
+            None,
+            None,
+            dim,
+            cell_size
+        )));
+    }
+
+    #[test]
+    fn test_combined_stroke_colors_cover_the_height_cells() {
+        let pattern = vec![
+            ([0, 0], vec![([3, 4], 1.0), ([4, 4], 0.5)]),
+            ([1, 0], vec![([0, 4], 0.5)]),
+            // Not loaded: neither heights nor colors are written for it
+            ([5, 5], vec![([1, 1], 1.0)]),
+        ];
+        let loaded = |chunk: [i32; 2]| chunk != [5, 5];
+        let old = (
+            Color::from_rgba(1.0, 0.0, 0.0, 0.0),
+            Color::from_rgba(0.0, 0.0, 0.0, 0.0),
+        );
+        let paint = (
+            Color::from_rgba(0.0, 1.0, 0.0, 0.0),
+            Color::from_rgba(0.0, 0.0, 1.0, 0.0),
+        );
+
+        // The cells draw_pattern's Phase 1 writes do_height entries for
+        let do_height: HashSet<([i32; 2], [i32; 2])> = stroke_cells(&pattern, loaded)
+            .into_iter()
+            .flat_map(|(chunk, cells)| cells.iter().map(move |&(cell, _)| (*chunk, cell)))
+            .collect();
+
+        let colors =
+            combined_stroke_colors(&pattern, paint, |chunk, _| loaded(chunk).then_some(old));
+        let do_colors: HashSet<([i32; 2], [i32; 2])> = colors
+            .iter()
+            .flat_map(|(chunk, cells)| cells.keys().map(move |cell| (*chunk, *cell)))
+            .collect();
+        assert_eq!(do_colors, do_height);
+        for cells in colors.values() {
+            for &(do_pair, undo_pair) in cells.values() {
+                assert_eq!(do_pair, paint);
+                assert_eq!(undo_pair, old);
+            }
+        }
+    }
+
+    #[test]
+    fn test_step_does_not_blend_into_neighbor_chunks() {
+        assert!(is_height_mode(TerrainToolMode::Step));
+        assert!(!blends_into_neighbors(TerrainToolMode::Step));
+        for mode in [
+            TerrainToolMode::Height,
+            TerrainToolMode::Sculpt,
+            TerrainToolMode::Smooth,
+            TerrainToolMode::Bridge,
+        ] {
+            assert!(blends_into_neighbors(mode), "{mode:?}");
+        }
+        assert!(!blends_into_neighbors(TerrainToolMode::VertexPaint));
     }
 }
@@ -7,7 +7,7 @@ use godot::classes::{
 };
 use godot::prelude::*;
 
-use crate::editor_plugin::{BrushType, PixyTerrainPlugin, TerrainToolMode};
+use crate::editor_plugin::{bridge_height, BrushType, PixyTerrainPlugin, TerrainToolMode};
 use crate::terrain::PixyTerrain;
 
 /// State snapshot passed from editor plugin to gizmo plugin.
@@ -27,6 +27,85 @@ pub struct GizmoState {
     pub draw_height_set: bool,
     /// Whether the plugin is in active drawing mode.
     pub is_drawing: bool,
+    /// Whether Ctrl is held (flips Sculpt/Contour direction).
+    pub ctrl_held: bool,
+    /// Whether a Bridge drag is in progress (start placed, waiting for release).
+    pub is_making_bridge: bool,
+    /// Where the Bridge drag started; the cursor is the other end.
+    pub bridge_start: Vector3,
+    /// Bridge slope shaping (-1 = linear), same as the tool attribute.
+    pub ease_value: f32,
+}
+
+/// Evenly spaced points along a bridge from `start` to `end`, heights following
+/// the same eased slope the Bridge tool writes on release.
+pub fn bridge_preview_points(
+    start: Vector3,
+    end: Vector3,
+    ease_value: f32,
+    count: usize,
+) -> Vec<Vector3> {
+    let count = count.max(2);
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / (count - 1) as f32;
+            Vector3::new(
+                start.x + (end.x - start.x) * t,
+                bridge_height(start.y, end.y, t, ease_value),
+                start.z + (end.z - start.z) * t,
+            )
+        })
+        .collect()
+}
+
+/// What the next stroke does to the terrain; picks the brush outline color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrushIntent {
+    Raise,
+    Lower,
+    Level,
+    Paint,
+    Neutral,
+}
+
+impl BrushIntent {
+    /// Work out the intent from the tool mode. `height_delta` is how far the brush sits above
+    /// the stroke's base height while a Height stroke is being set (None otherwise).
+    pub fn from_mode(
+        mode: TerrainToolMode,
+        flatten: bool,
+        ctrl_held: bool,
+        height_delta: Option<f32>,
+    ) -> Self {
+        match mode {
+            TerrainToolMode::Height if flatten => BrushIntent::Level,
+            TerrainToolMode::Height => match height_delta {
+                Some(d) if d > 0.0 => BrushIntent::Raise,
+                Some(d) if d < 0.0 => BrushIntent::Lower,
+                _ => BrushIntent::Neutral,
+            },
+            TerrainToolMode::Sculpt if ctrl_held => BrushIntent::Lower,
+            TerrainToolMode::Sculpt => BrushIntent::Raise,
+            TerrainToolMode::Contour if ctrl_held => BrushIntent::Raise,
+            TerrainToolMode::Contour => BrushIntent::Lower,
+            TerrainToolMode::Step if ctrl_held => BrushIntent::Lower,
+            TerrainToolMode::Step => BrushIntent::Raise,
+            TerrainToolMode::Level | TerrainToolMode::Smooth => BrushIntent::Level,
+            TerrainToolMode::VertexPaint | TerrainToolMode::GrassMask => BrushIntent::Paint,
+            _ => BrushIntent::Neutral,
+        }
+    }
+
+    /// Brush outline color: green raises, red lowers, blue levels, amber paints.
+    pub fn color(self) -> Color {
+        match self {
+            BrushIntent::Raise => Color::from_rgba(0.35, 1.0, 0.35, 0.8),
+            BrushIntent::Lower => Color::from_rgba(1.0, 0.35, 0.35, 0.8),
+            BrushIntent::Level => Color::from_rgba(0.35, 0.6, 1.0, 0.8),
+            BrushIntent::Paint => Color::from_rgba(1.0, 0.8, 0.3, 0.8),
+            BrushIntent::Neutral => Color::from_rgba(1.0, 1.0, 1.0, 0.7),
+        }
+    }
 }
 
 /// Gizmo plugin for PixyTerrain: brush preview, chunk grid overlay, draw pattern visualization.                                          
@@ -190,6 +269,11 @@ impl IEditorNode3DGizmoPlugin for PixyTerrainGizmoPlugin {
 
         // ── Brush circle/square visualization ──
         let brush_mat = self.base_mut().get_material("brush");
+        let height_delta = (state.is_setting && state.draw_height_set)
+            .then(|| state.brush_position.y - state.draw_height);
+        let intent =
+            BrushIntent::from_mode(state.mode, state.flatten, state.ctrl_held, height_delta);
+        self.set_preview_color(intent.color());
 
         if state.terrain_hovered {
             let pos = state.brush_position;
@@ -267,6 +351,25 @@ impl IEditorNode3DGizmoPlugin for PixyTerrainGizmoPlugin {
                     );
                 }
             }
+
+            // ── Bridge preview: eased span plus a height tick at each sample ──
+            if state.mode == TerrainToolMode::Bridge && state.is_making_bridge {
+                let points = bridge_preview_points(state.bridge_start, pos, state.ease_value, 17);
+                let mut bridge_lines = PackedVector3Array::new();
+                for pair in points.windows(2) {
+                    bridge_lines.push(pair[0] + Vector3::UP * gizmo_offset);
+                    bridge_lines.push(pair[1] + Vector3::UP * gizmo_offset);
+                }
+                for p in &points {
+                    let ground =
+                        sample_terrain_height(&t, p.x, p.z, dim, cell_size, p.y, gizmo_offset);
+                    bridge_lines.push(Vector3::new(p.x, ground, p.z));
+                    bridge_lines.push(*p + Vector3::UP * gizmo_offset);
+                }
+                if let Some(mat) = self.base_mut().get_material("bridge") {
+                    gizmo.add_lines(&bridge_lines, &mat.upcast::<godot::classes::Material>());
+                }
+            }
         }
 
         drop(t);
@@ -278,6 +381,13 @@ impl IEditorNode3DGizmoPlugin for PixyTerrainGizmoPlugin {
 }
 
 impl PixyTerrainGizmoPlugin {
+    /// Tint the brush outline (e.g. with `BrushIntent::color`).
+    pub fn set_preview_color(&mut self, color: Color) {
+        if let Some(mut mat) = self.base_mut().get_material("brush") {
+            mat.set_albedo(color);
+        }
+    }
+
     pub fn create_materials(&mut self) {
         let mut brush_mat = StandardMaterial3D::new_gd();
         brush_mat.set_depth_draw_mode(DepthDrawMode::DISABLED);
@@ -293,6 +403,13 @@ impl PixyTerrainGizmoPlugin {
         pattern_mat.set_albedo(Color::from_rgba(0.7, 0.7, 0.7, 0.6));
         self.base_mut().add_material("brush_pattern", &pattern_mat);
 
+        let mut bridge_mat = StandardMaterial3D::new_gd();
+        bridge_mat.set_depth_draw_mode(DepthDrawMode::DISABLED);
+        bridge_mat.set_shading_mode(ShadingMode::UNSHADED);
+        bridge_mat.set_transparency(Transparency::ALPHA);
+        bridge_mat.set_albedo(Color::from_rgba(1.0, 0.85, 0.3, 0.8));
+        self.base_mut().add_material("bridge", &bridge_mat);
+
         self.base_mut()
             .create_material("removechunk", Color::from_rgba(1.0, 0.0, 0.0, 0.5));
         self.base_mut()
@@ -391,3 +508,49 @@ fn draw_chunk_lines(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowering_strokes_tint_the_brush_red() {
+        let lower = [
+            BrushIntent::from_mode(TerrainToolMode::Height, false, false, Some(-2.0)),
+            BrushIntent::from_mode(TerrainToolMode::Sculpt, false, true, None),
+            BrushIntent::from_mode(TerrainToolMode::Contour, false, false, None),
+        ];
+        for intent in lower {
+            assert_eq!(intent, BrushIntent::Lower);
+            let c = intent.color();
+            assert!(c.r > c.g && c.r > c.b, "{intent:?} is not red: {c:?}");
+        }
+
+        let raise = BrushIntent::from_mode(TerrainToolMode::Height, false, false, Some(2.0));
+        assert_eq!(raise, BrushIntent::Raise);
+        assert!(raise.color().g > raise.color().r);
+        assert_eq!(
+            BrushIntent::from_mode(TerrainToolMode::Height, true, false, Some(-2.0)),
+            BrushIntent::Level
+        );
+    }
+
+    #[test]
+    fn test_bridge_preview_follows_ease() {
+        let start = Vector3::new(0.0, 2.0, 0.0);
+        let end = Vector3::new(8.0, 10.0, 4.0);
+
+        let linear = bridge_preview_points(start, end, -1.0, 5);
+        assert_eq!(linear.len(), 5);
+        assert_eq!(linear[0], start);
+        assert_eq!(linear[4], end);
+        assert!((linear[2].y - 6.0).abs() < 1e-5);
+        assert!((linear[2].x - 4.0).abs() < 1e-5 && (linear[2].z - 2.0).abs() < 1e-5);
+
+        // ease(0.5, 2.0) = 0.25: the span stays low near the start.
+        let eased = bridge_preview_points(start, end, 2.0, 5);
+        assert!((eased[2].y - 4.0).abs() < 1e-5);
+        assert_eq!(eased[4], end);
+        assert_eq!(eased[2].x, linear[2].x);
+    }
+}
@@ -0,0 +1,222 @@
+// Pixy Terrain — Chunk grid coordinates
+//
+// Each chunk spans `dimensions - 1` cells per axis, so neighboring chunks share their seam
+// vertices. Terrain-wide ("global") vertex coords number every vertex once:
+// `chunk * (dimensions - 1) + cell`. All positions here are terrain-local.
+
+use godot::prelude::*;
+
+/// XZ size of one chunk.
+pub fn chunk_size(dim: Vector3i, cell_size: Vector2) -> Vector2 {
+    Vector2::new(
+        (dim.x - 1) as f32 * cell_size.x,
+        (dim.z - 1) as f32 * cell_size.y,
+    )
+}
+
+/// Terrain-wide vertex coords of vertex `cell` in chunk `chunk`.
+pub fn global_vertex(chunk: [i32; 2], cell: [i32; 2], dim: Vector3i) -> [i32; 2] {
+    [
+        chunk[0] * (dim.x - 1) + cell[0],
+        chunk[1] * (dim.z - 1) + cell[1],
+    ]
+}
+
+/// XZ of heightmap vertex `cell` in chunk `chunk`.
+pub fn vertex_position(
+    chunk: [i32; 2],
+    cell: [i32; 2],
+    dim: Vector3i,
+    cell_size: Vector2,
+) -> Vector2 {
+    let [gx, gz] = global_vertex(chunk, cell, dim);
+    Vector2::new(gx as f32 * cell_size.x, gz as f32 * cell_size.y)
+}
+
+/// Chunk containing XZ `pos`. Seam vertices belong to the chunk on their +X/+Z side.
+pub fn chunk_coords_at(pos: Vector2, dim: Vector3i, cell_size: Vector2) -> [i32; 2] {
+    let size = chunk_size(dim, cell_size);
+    [
+        (pos.x / size.x).floor() as i32,
+        (pos.y / size.y).floor() as i32,
+    ]
+}
+
+/// Map an XZ position to the chunk containing it and the nearest cell vertex.
+pub fn world_to_chunk_cell(
+    pos: Vector2,
+    dim: Vector3i,
+    cell_size: Vector2,
+) -> ([i32; 2], [i32; 2]) {
+    let chunk = chunk_coords_at(pos, dim, cell_size);
+    let [origin_x, origin_z] = global_vertex(chunk, [0, 0], dim);
+    let cell_x = (pos.x / cell_size.x - origin_x as f32).round() as i32;
+    let cell_z = (pos.y / cell_size.y - origin_z as f32).round() as i32;
+    (
+        chunk,
+        [cell_x.clamp(0, dim.x - 1), cell_z.clamp(0, dim.z - 1)],
+    )
+}
+
+/// XZ center of a chunk, matching the placement in `PixyTerrain::add_chunk_internal`.
+pub fn chunk_center_xz(coords: Vector2i, dimensions: Vector3i, cell_size: Vector2) -> Vector2 {
+    let origin = vertex_position([coords.x, coords.y], [0, 0], dimensions, cell_size);
+    origin + chunk_size(dimensions, cell_size) * 0.5
+}
+
+/// AABB of a chunk's grid with Y spanning `min_y..=max_y`.
+pub fn chunk_aabb(
+    coords: Vector2i,
+    dimensions: Vector3i,
+    cell_size: Vector2,
+    min_y: f32,
+    max_y: f32,
+) -> Aabb {
+    let origin = vertex_position([coords.x, coords.y], [0, 0], dimensions, cell_size);
+    let size = chunk_size(dimensions, cell_size);
+    Aabb::new(
+        Vector3::new(origin.x, min_y, origin.y),
+        Vector3::new(size.x, max_y - min_y, size.y),
+    )
+}
+
+/// Every `(chunk key, local cell)` that stores terrain-wide vertex `(gx, gz)`: one chunk for
+/// interior vertices, two on a seam and four at a chunk corner.
+pub fn vertex_owners(gx: i32, gz: i32, dim: Vector3i) -> Vec<([i32; 2], [i32; 2])> {
+    let axis = |g: i32, span: i32| {
+        let (chunk, local) = (g.div_euclid(span), g.rem_euclid(span));
+        let mut owners = vec![(chunk, local)];
+        if local == 0 {
+            owners.push((chunk - 1, span));
+        }
+        owners
+    };
+    let xs = axis(gx, dim.x - 1);
+    let zs = axis(gz, dim.z - 1);
+    zs.iter()
+        .flat_map(|&(cz, z)| xs.iter().map(move |&(cx, x)| ([cx, cz], [x, z])))
+        .collect()
+}
+
+/// Terrain-wide vertex coords covered by chunk `key`, seam vertices included.
+pub fn chunk_vertices(key: [i32; 2], dim: Vector3i) -> impl Iterator<Item = [i32; 2]> {
+    let origin = global_vertex(key, [0, 0], dim);
+    (0..dim.z).flat_map(move |z| (0..dim.x).map(move |x| [origin[0] + x, origin[1] + z]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMS: Vector3i = Vector3i::new(33, 32, 33);
+    const CELL: Vector2 = Vector2::new(2.0, 2.0);
+
+    #[test]
+    fn test_world_to_chunk_cell() {
+        assert_eq!(
+            world_to_chunk_cell(Vector2::new(0.0, 0.0), DIMS, CELL),
+            ([0, 0], [0, 0])
+        );
+        assert_eq!(
+            world_to_chunk_cell(Vector2::new(10.9, 4.2), DIMS, CELL),
+            ([0, 0], [5, 2])
+        );
+        assert_eq!(
+            world_to_chunk_cell(Vector2::new(70.0, -2.0), DIMS, CELL),
+            ([1, -1], [3, 31])
+        );
+    }
+
+    #[test]
+    fn test_chunk_coords_at_known_points() {
+        // 33x33 vertices at 2.0 spacing: each chunk spans 64 units
+        for (pos, expected) in [
+            (Vector2::new(0.0, 0.0), [0, 0]),
+            (Vector2::new(63.9, 10.0), [0, 0]),
+            (Vector2::new(64.0, 10.0), [1, 0]),
+            (Vector2::new(130.0, 200.0), [2, 3]),
+            (Vector2::new(-0.1, 5.0), [-1, 0]),
+            (Vector2::new(-64.0, -64.1), [-1, -2]),
+        ] {
+            assert_eq!(chunk_coords_at(pos, DIMS, CELL), expected, "pos {pos:?}");
+        }
+    }
+
+    #[test]
+    fn test_chunk_coords_at_lands_inside_chunk_aabb() {
+        let dim = Vector3i::new(17, 16, 9);
+        let cell = Vector2::new(1.5, 3.0);
+        for pos in [
+            Vector2::new(-40.0, 7.0),
+            Vector2::new(100.0, -100.0),
+            Vector2::new(0.5, 0.5),
+        ] {
+            let [x, z] = chunk_coords_at(pos, dim, cell);
+            let aabb = chunk_aabb(Vector2i::new(x, z), dim, cell, 0.0, 1.0);
+            assert!(aabb.position.x <= pos.x && pos.x < aabb.end().x);
+            assert!(aabb.position.z <= pos.y && pos.y < aabb.end().z);
+        }
+    }
+
+    #[test]
+    fn test_chunk_center_xz() {
+        assert_eq!(
+            chunk_center_xz(Vector2i::ZERO, DIMS, CELL),
+            Vector2::new(32.0, 32.0)
+        );
+        assert_eq!(
+            chunk_center_xz(Vector2i::new(-1, 2), DIMS, CELL),
+            Vector2::new(-32.0, 160.0)
+        );
+    }
+
+    #[test]
+    fn test_chunk_aabbs_tile_without_gaps_or_overlaps() {
+        let coords: Vec<Vector2i> = (0..2)
+            .flat_map(|z| (0..2).map(move |x| Vector2i::new(x, z)))
+            .collect();
+        let boxes: Vec<Aabb> = coords
+            .iter()
+            .map(|c| chunk_aabb(*c, DIMS, CELL, -1.0, 4.0))
+            .collect();
+
+        // Areas sum to the full 2x2 map footprint
+        let total_area: f32 = boxes.iter().map(|b| b.size.x * b.size.z).sum();
+        assert_eq!(total_area, 128.0 * 128.0);
+
+        for (i, a) in boxes.iter().enumerate() {
+            assert!(a.position.x >= 0.0 && a.position.x + a.size.x <= 128.0);
+            assert!(a.position.z >= 0.0 && a.position.z + a.size.z <= 128.0);
+            for b in boxes.iter().skip(i + 1) {
+                let overlap_x = (a.position.x + a.size.x).min(b.position.x + b.size.x)
+                    - a.position.x.max(b.position.x);
+                let overlap_z = (a.position.z + a.size.z).min(b.position.z + b.size.z)
+                    - a.position.z.max(b.position.z);
+                assert!(overlap_x <= 0.0 || overlap_z <= 0.0, "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_vertex_owners_on_interior_seam_and_corner() {
+        assert_eq!(vertex_owners(5, 40, DIMS), vec![([0, 1], [5, 8])]);
+        assert_eq!(
+            vertex_owners(32, 40, DIMS),
+            vec![([1, 1], [0, 8]), ([0, 1], [32, 8])]
+        );
+        let corner = vertex_owners(-32, 0, DIMS);
+        assert_eq!(corner.len(), 4);
+        assert!(corner.contains(&([-1, 0], [0, 0])));
+        assert!(corner.contains(&([-2, -1], [32, 32])));
+    }
+
+    #[test]
+    fn test_every_owner_maps_back_to_the_same_vertex() {
+        for (gx, gz) in [(0, 0), (32, 7), (-33, 64), (100, -1)] {
+            for (chunk, cell) in vertex_owners(gx, gz, DIMS) {
+                assert_eq!(global_vertex(chunk, cell, DIMS), [gx, gz]);
+                assert!(chunk_vertices(chunk, DIMS).any(|v| v == [gx, gz]));
+            }
+        }
+    }
+}
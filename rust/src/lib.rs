@@ -5,6 +5,7 @@ mod editor_plugin;
 mod flower_planter;
 mod gizmo;
 mod grass_planter;
+mod grid;
 mod marching_squares;
 mod quick_paint;
 mod shader_sync;
@@ -670,6 +670,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_texture_index_to_colors_covers_all_slots() {
+        let mut seen = Vec::new();
+        for idx in 0..16 {
+            let (c0, c1) = texture_index_to_colors(idx);
+            for c in [c0, c1] {
+                let channels = [c.r, c.g, c.b, c.a];
+                assert_eq!(channels.iter().filter(|&&v| v == 1.0).count(), 1);
+                assert_eq!(channels.iter().filter(|&&v| v == 0.0).count(), 3);
+            }
+            assert!(!seen.contains(&(c0, c1)), "index {idx} reuses a color pair");
+            seen.push((c0, c1));
+            assert_eq!(colors_to_texture_index(c0, c1), idx);
+        }
+    }
+
+    #[test]
+    fn test_colors_to_texture_index_rejects_unmatched_colors() {
+        let red = Color::from_rgba(1.0, 0.0, 0.0, 0.0);
+        let blend = Color::from_rgba(0.5, 0.5, 0.0, 0.0);
+        let empty = Color::from_rgba(0.0, 0.0, 0.0, 0.0);
+        assert_eq!(colors_to_texture_index(red, red), 0);
+        assert_eq!(colors_to_texture_index(blend, red), -1);
+        assert_eq!(colors_to_texture_index(red, empty), -1);
+        assert_eq!(colors_to_texture_index(Color::WHITE, Color::WHITE), -1);
+    }
+
     #[test]
     fn test_get_dominant_color() {
         let c = get_dominant_color(Color::from_rgba(0.3, 0.8, 0.1, 0.2));
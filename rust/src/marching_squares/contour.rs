@@ -0,0 +1,147 @@
+use godot::prelude::*;
+
+/// Iso-height contour of a heightmap as line segments in grid-local XZ.
+///
+/// `heights` is indexed `[z][x]`; vertex `(x, z)` sits at `(x * cell_size.x, z * cell_size.y)`.
+/// Corners at or above `level` count as inside. Crossing points are interpolated along
+/// each cell edge, so neighboring cells (and chunks sharing an edge row) emit
+/// bit-identical endpoints and the segments chain into closed loops.
+pub fn contour_segments(heights: &[Vec<f32>], level: f32, cell_size: Vector2) -> Vec<[Vector2; 2]> {
+    let mut segments = Vec::new();
+    if heights.len() < 2 {
+        return segments;
+    }
+
+    for z in 0..heights.len() - 1 {
+        let row = &heights[z];
+        let next = &heights[z + 1];
+        let width = row.len().min(next.len());
+        for x in 0..width.saturating_sub(1) {
+            // Corners: a = top-left, b = top-right, c = bottom-right, d = bottom-left
+            let (ha, hb, hc, hd) = (row[x], row[x + 1], next[x + 1], next[x]);
+            let pa = Vector2::new(x as f32 * cell_size.x, z as f32 * cell_size.y);
+            let pb = Vector2::new((x + 1) as f32 * cell_size.x, pa.y);
+            let pc = Vector2::new(pb.x, (z + 1) as f32 * cell_size.y);
+            let pd = Vector2::new(pa.x, pc.y);
+
+            // Edge order: top (a→b), right (b→c), bottom (d→c), left (a→d)
+            let top = edge_crossing(pa, ha, pb, hb, level);
+            let right = edge_crossing(pb, hb, pc, hc, level);
+            let bottom = edge_crossing(pd, hd, pc, hc, level);
+            let left = edge_crossing(pa, ha, pd, hd, level);
+
+            match (top, right, bottom, left) {
+                (Some(t), Some(r), Some(b), Some(l)) => {
+                    // Saddle: the cell center decides which diagonal pair is connected
+                    let center_inside = (ha + hb + hc + hd) * 0.25 >= level;
+                    if (ha >= level) != center_inside {
+                        segments.push([t, l]);
+                        segments.push([r, b]);
+                    } else {
+                        segments.push([t, r]);
+                        segments.push([b, l]);
+                    }
+                }
+                crossings => {
+                    let points: Vec<Vector2> = [crossings.0, crossings.1, crossings.2, crossings.3]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    if let [p0, p1] = points[..] {
+                        segments.push([p0, p1]);
+                    }
+                }
+            }
+        }
+    }
+
+    segments
+}
+
+/// Point where the edge `p0→p1` crosses `level`, if its endpoints straddle it.
+fn edge_crossing(p0: Vector2, h0: f32, p1: Vector2, h1: f32, level: f32) -> Option<Vector2> {
+    if (h0 >= level) == (h1 >= level) {
+        return None;
+    }
+    let t = (level - h0) / (h1 - h0);
+    Some(p0 + (p1 - p0) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cone peaking at the grid center, sloping down 1 unit per grid step.
+    fn cone(size: usize, peak: f32) -> Vec<Vec<f32>> {
+        let center = (size - 1) as f32 * 0.5;
+        (0..size)
+            .map(|z| {
+                (0..size)
+                    .map(|x| {
+                        let dx = x as f32 - center;
+                        let dz = z as f32 - center;
+                        peak - (dx * dx + dz * dz).sqrt()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn key(p: Vector2) -> [u32; 2] {
+        [p.x.to_bits(), p.y.to_bits()]
+    }
+
+    #[test]
+    fn test_cone_contour_is_closed_loop() {
+        let heights = cone(17, 10.0);
+        let segments = contour_segments(&heights, 5.5, Vector2::new(2.0, 2.0));
+        assert!(
+            segments.len() > 4,
+            "expected a ring, got {} segments",
+            segments.len()
+        );
+
+        // Every endpoint is shared by exactly two segments
+        let mut uses: std::collections::HashMap<[u32; 2], usize> = Default::default();
+        for [a, b] in &segments {
+            *uses.entry(key(*a)).or_default() += 1;
+            *uses.entry(key(*b)).or_default() += 1;
+        }
+        assert!(uses.values().all(|&n| n == 2), "open contour: {:?}", uses);
+
+        // Walking from the first segment visits all of them and returns to the start
+        let mut remaining: Vec<[Vector2; 2]> = segments[1..].to_vec();
+        let start = segments[0][0];
+        let mut cursor = segments[0][1];
+        while key(cursor) != key(start) {
+            let i = remaining
+                .iter()
+                .position(|s| key(s[0]) == key(cursor) || key(s[1]) == key(cursor))
+                .expect("contour chain broken");
+            let [a, b] = remaining.swap_remove(i);
+            cursor = if key(a) == key(cursor) { b } else { a };
+        }
+        assert!(
+            remaining.is_empty(),
+            "{} segments not on the loop",
+            remaining.len()
+        );
+    }
+
+    #[test]
+    fn test_contour_empty_when_plane_misses_surface() {
+        let heights = cone(9, 10.0);
+        let cell = Vector2::new(1.0, 1.0);
+        assert!(contour_segments(&heights, 20.0, cell).is_empty());
+        assert!(contour_segments(&heights, -20.0, cell).is_empty());
+    }
+
+    #[test]
+    fn test_contour_points_lie_on_level() {
+        let heights = vec![vec![0.0, 4.0], vec![0.0, 4.0]];
+        let segments = contour_segments(&heights, 1.0, Vector2::new(2.0, 2.0));
+        assert_eq!(segments.len(), 1);
+        let [a, b] = segments[0];
+        assert!((a.x - 0.5).abs() < 1e-6 && (b.x - 0.5).abs() < 1e-6);
+    }
+}
@@ -4,6 +4,7 @@
 //   https://github.com/Yukitty/Yugens-Terrain-Authoring-Toolkit
 mod cases;
 mod cell_context;
+mod contour;
 mod primitives;
 mod types;
 mod validator;
@@ -11,6 +12,7 @@ mod vertex;
 
 pub use cases::*;
 pub use cell_context::*;
+pub use contour::*;
 pub use primitives::*;
 pub use types::*;
 pub use validator::*;
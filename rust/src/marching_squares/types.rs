@@ -184,6 +184,15 @@ pub fn texture_index_to_colors(idx: i32) -> (Color, Color) {
     TextureIndex(idx as u8).to_color_pair()
 }
 
+/// Inverse of `texture_index_to_colors`: the texture index (0-15) whose color pair is
+/// exactly `(c0, c1)`, or -1 for any other pair. Use `TextureIndex::from_color_pair` to
+/// resolve blended colors by dominant channel instead.
+pub fn colors_to_texture_index(c0: Color, c1: Color) -> i32 {
+    (0..16)
+        .find(|&idx| texture_index_to_colors(idx) == (c0, c1))
+        .unwrap_or(-1)
+}
+
 // ================================
 // ===== Boundary Profiles ========
 // ================================
@@ -113,6 +113,88 @@ pub fn validate_cell_watertight(
     }
 }
 
+/// Edge-use counts over a triangle list, for diagnosing watertightness of a whole mesh.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ManifoldReport {
+    /// Edges used by exactly one triangle (open boundary).
+    pub boundary_edges: usize,
+    /// Edges shared by exactly two triangles.
+    pub manifold_edges: usize,
+    /// Edges shared by three or more triangles.
+    pub non_manifold_edges: usize,
+}
+
+/// Count boundary, manifold, and non-manifold edges in a triangle list (3 verts per triangle).
+///
+/// Uses the same bit-exact edge keys as `validate_cell_watertight`, so vertices must match exactly
+/// to be considered shared.
+pub fn check_manifold(verts: &[Vector3]) -> ManifoldReport {
+    let mut edge_counts: HashMap<EdgeKey, u32> = HashMap::new();
+    for tri in verts.chunks_exact(3) {
+        for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+            *edge_counts.entry(make_edge_key(a, b)).or_insert(0) += 1;
+        }
+    }
+
+    let mut report = ManifoldReport::default();
+    for count in edge_counts.values() {
+        match count {
+            1 => report.boundary_edges += 1,
+            2 => report.manifold_edges += 1,
+            _ => report.non_manifold_edges += 1,
+        }
+    }
+    report
+}
+
+/// Drop triangles that have two bit-identical vertices, or (when `area_epsilon > 0`) an area
+/// below `area_epsilon`, keeping every per-vertex attribute array aligned. Returns the number
+/// of triangles removed.
+///
+/// Removing a triangle with a repeated vertex never opens the mesh. A thin but non-collinear
+/// sliver's edges are shared with its neighbors, so a non-zero epsilon can leave open edges.
+pub fn remove_degenerate_triangles(geo: &mut CellGeometry, area_epsilon: f32) -> usize {
+    let tri_count = geo.verts.len() / 3;
+    let keep: Vec<bool> = geo
+        .verts
+        .chunks_exact(3)
+        .map(|t| {
+            let (a, b, c) = (
+                vertex_to_bits(t[0]),
+                vertex_to_bits(t[1]),
+                vertex_to_bits(t[2]),
+            );
+            if a == b || b == c || c == a {
+                return false;
+            }
+            area_epsilon <= 0.0 || (t[1] - t[0]).cross(t[2] - t[0]).length() * 0.5 >= area_epsilon
+        })
+        .collect();
+    let removed = keep.iter().filter(|k| !**k).count();
+    if removed == 0 {
+        return 0;
+    }
+
+    fn retain_tris<T>(values: &mut Vec<T>, keep: &[bool]) {
+        let mut i = 0;
+        values.retain(|_| {
+            let kept = keep.get(i / 3).copied().unwrap_or(true);
+            i += 1;
+            kept
+        });
+    }
+    retain_tris(&mut geo.verts, &keep);
+    retain_tris(&mut geo.uvs, &keep);
+    retain_tris(&mut geo.uv2s, &keep);
+    retain_tris(&mut geo.colors_0, &keep);
+    retain_tris(&mut geo.colors_1, &keep);
+    retain_tris(&mut geo.grass_mask, &keep);
+    retain_tris(&mut geo.material_blend, &keep);
+    retain_tris(&mut geo.is_floor, &keep);
+    debug_assert_eq!(geo.verts.len(), (tri_count - removed) * 3);
+    removed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,6 +206,128 @@ mod tests {
         CellContext::test_default(3, 3)
     }
 
+    #[test]
+    fn test_manifold_closed_tetrahedron() {
+        let p0 = Vector3::new(0.0, 0.0, 0.0);
+        let p1 = Vector3::new(1.0, 0.0, 0.0);
+        let p2 = Vector3::new(0.0, 1.0, 0.0);
+        let p3 = Vector3::new(0.0, 0.0, 1.0);
+        let verts = [p0, p2, p1, p0, p1, p3, p0, p3, p2, p1, p2, p3];
+        let report = check_manifold(&verts);
+        assert_eq!(
+            report,
+            ManifoldReport {
+                boundary_edges: 0,
+                manifold_edges: 6,
+                non_manifold_edges: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_manifold_detects_fin_edge() {
+        // Three triangles hinged on the same edge (a T-junction / fin)
+        let a = Vector3::new(0.0, 0.0, 0.0);
+        let b = Vector3::new(1.0, 0.0, 0.0);
+        let verts = [
+            a,
+            b,
+            Vector3::new(0.5, 0.0, 1.0),
+            b,
+            a,
+            Vector3::new(0.5, 0.0, -1.0),
+            a,
+            b,
+            Vector3::new(0.5, 1.0, 0.0),
+        ];
+        let report = check_manifold(&verts);
+        assert_eq!(report.non_manifold_edges, 1);
+        assert_eq!(report.manifold_edges, 0);
+        assert_eq!(report.boundary_edges, 6);
+    }
+
+    #[test]
+    fn test_manifold_generated_cell_has_no_fins() {
+        let mut ctx = default_context();
+        ctx.heights = [7.0, 5.0, 3.0, 3.0];
+        let mut geo = CellGeometry::default();
+        generate_cell(&mut ctx, &mut geo);
+        let report = check_manifold(&geo.verts);
+        assert_eq!(report.non_manifold_edges, 0);
+        assert!(report.manifold_edges > 0);
+    }
+
+    /// Append one triangle to `geo`, copying attributes from vertex 0.
+    fn push_triangle(geo: &mut CellGeometry, verts: [Vector3; 3]) {
+        for v in verts {
+            geo.verts.push(v);
+            geo.uvs.push(geo.uvs[0]);
+            geo.uv2s.push(geo.uv2s[0]);
+            geo.colors_0.push(geo.colors_0[0]);
+            geo.colors_1.push(geo.colors_1[0]);
+            geo.grass_mask.push(geo.grass_mask[0]);
+            geo.material_blend.push(geo.material_blend[0]);
+            geo.is_floor.push(geo.is_floor[0]);
+        }
+    }
+
+    fn assert_attributes_aligned(geo: &CellGeometry) {
+        let n = geo.verts.len();
+        assert_eq!(n % 3, 0);
+        for len in [
+            geo.uvs.len(),
+            geo.uv2s.len(),
+            geo.colors_0.len(),
+            geo.colors_1.len(),
+            geo.grass_mask.len(),
+            geo.material_blend.len(),
+            geo.is_floor.len(),
+        ] {
+            assert_eq!(len, n);
+        }
+    }
+
+    #[test]
+    fn test_remove_degenerate_drops_inserted_slivers() {
+        let mut ctx = default_context();
+        ctx.heights = [7.0, 5.0, 3.0, 3.0];
+        let mut clean = CellGeometry::default();
+        generate_cell(&mut ctx, &mut clean);
+        let clean_verts = clean.verts.clone();
+
+        let mut geo = clean.clone();
+        let p = Vector3::new(0.5, 3.0, 0.5);
+        let q = Vector3::new(1.5, 3.0, 0.5);
+        // Repeated vertex and a collinear (zero-area) triangle
+        push_triangle(&mut geo, [p, p, q]);
+        push_triangle(&mut geo, [p, (p + q) * 0.5, q]);
+
+        // Without an epsilon only the repeated-vertex triangle goes
+        let mut repeated_only = geo.clone();
+        assert_eq!(remove_degenerate_triangles(&mut repeated_only, 0.0), 1);
+        assert_eq!(repeated_only.verts.len(), clean_verts.len() + 3);
+
+        assert_eq!(remove_degenerate_triangles(&mut geo, 1e-6), 2);
+        assert_attributes_aligned(&geo);
+        assert_eq!(geo.verts, clean_verts);
+        assert_eq!(check_manifold(&geo.verts), check_manifold(&clean_verts));
+    }
+
+    #[test]
+    fn test_remove_degenerate_keeps_cells_watertight() {
+        for heights in [[5.0; 4], [7.0, 5.0, 3.0, 3.0], [0.0, 5.0, 0.0, 5.0]] {
+            let mut ctx = default_context();
+            ctx.heights = heights;
+            let mut geo = CellGeometry::default();
+            generate_cell(&mut ctx, &mut geo);
+            remove_degenerate_triangles(&mut geo, 0.0);
+            assert_attributes_aligned(&geo);
+            assert!(!geo.verts.is_empty(), "{heights:?}");
+            let result = validate_cell_watertight(&geo, 0, 0, ctx.config.cell_size);
+            assert!(result.is_watertight, "{heights:?} opened after cleanup");
+        }
+    }
+
     fn validate_case(heights: [f32; 4], label: &str) {
         let mut ctx = default_context();
         ctx.heights = heights;
@@ -6,24 +6,50 @@
 // Grass/cloud shader integration adapted from Dylearn's 3D Pixel Art Grass Demo:
 //   https://github.com/DylearnDev/Dylearn-3D-Pixel-Art-Grass-Demo
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
+use godot::classes::base_material_3d::{ShadingMode, Transparency};
+use godot::classes::file_access::ModeFlags;
+use godot::classes::mesh::PrimitiveType;
 use godot::classes::{
-    rendering_server::GlobalShaderParameterType, Engine, Image, ImageTexture, Mesh, Node3D,
-    RenderingServer, ResourceLoader, Shader, ShaderMaterial, Texture2D,
+    rendering_server::GlobalShaderParameterType, CollisionShape3D, ConcavePolygonShape3D, Curve3D,
+    Engine, FileAccess, Image, ImageTexture, ImmediateMesh, Material, Mesh, MeshInstance3D, Node3D,
+    RenderingServer, ResourceLoader, Shader, ShaderMaterial, StandardMaterial3D, StaticBody3D,
+    Texture2D,
 };
 use godot::prelude::*;
 
-use crate::chunk::{PixyTerrainChunk, TerrainConfig};
+use crate::chunk::{noise_height, PixyTerrainChunk, TerrainConfig};
 use crate::flower_planter::FlowerConfig;
 use crate::grass_planter::GrassConfig;
-use crate::marching_squares::{BlendMode, MergeMode};
+use crate::grid::{
+    chunk_aabb, chunk_coords_at, chunk_vertices, global_vertex, vertex_owners, vertex_position,
+};
+use crate::marching_squares::{
+    colors_to_texture_index, contour_segments, texture_index_to_colors, BlendMode, MergeMode,
+    TextureIndex,
+};
 use crate::shared_params::SharedTerrainParams;
 
 /// Path to the terrain shader file.
 const TERRAIN_SHADER_PATH: &str =
     "res://addons/pixy_terrain/resources/shaders/mst_terrain.gdshader";
 
+/// Unshaded wireframe used by `set_debug_wireframe` to inspect chunk topology.
+const WIREFRAME_SHADER_CODE: &str = "shader_type spatial;
+render_mode unshaded, wireframe, cull_disabled;
+
+void fragment() {
+	ALBEDO = vec3(0.1, 1.0, 0.4);
+}
+";
+
+/// Seconds the `debug_draw_affected_chunks` outlines take to fade out.
+const AFFECTED_OUTLINE_FADE_SECS: f32 = 1.0;
+
+/// Format version written by `save_terrain`; `load_terrain` rejects anything else.
+const TERRAIN_FILE_VERSION: i32 = 1;
+
 /// Path to the default ground noise texture.
 const DEFAULT_GROUND_TEXTURE_PATH: &str =
     "res://addons/pixy_terrain/resources/textures/default_ground_noise.tres";
@@ -57,10 +83,39 @@ pub struct PixyTerrain {
     #[export]
     pub noise_hmap: Option<Gd<godot::classes::Noise>>,
 
+    /// Multiplier on noise heights; change at runtime with `set_noise_amplitude`
+    #[export(range = (0.0, 4.0, 0.01))]
+    #[init(val = 1.0)]
+    pub noise_amplitude: f32,
+
+    /// Quantize noise heights into terraces this tall; 0 = disabled
+    #[export(range = (0.0, 32.0, 0.1))]
+    #[init(val = 0.0)]
+    pub terrace_height: f32,
+
+    /// Terrace edges: 0 = soft ramps, 1 = hard steps
+    #[export(range = (0.0, 1.0, 0.01))]
+    #[init(val = 0.5)]
+    pub terrace_sharpness: f32,
+
     #[export]
     #[init(val = 9)]
     pub extra_collision_layer: i32,
 
+    #[export]
+    #[init(val = true)]
+    pub generate_collision: bool,
+
+    /// Chunks re-meshed per frame when an edit touches more than this many; 0 = no limit
+    #[export(range = (0.0, 64.0, 1.0))]
+    #[init(val = 8)]
+    pub max_remesh_per_frame: i32,
+
+    /// Drop mesh triangles with less area than this (repeated-vertex triangles are always dropped)
+    #[export(range = (0.0, 0.1, 0.0001))]
+    #[init(val = 0.0)]
+    pub degenerate_area_epsilon: f32,
+
     #[export]
     #[init(val = 1.0)]
     pub ridge_threshold: f32,
@@ -241,6 +296,10 @@ pub struct PixyTerrain {
     #[init(val = 3.0)]
     pub cross_section_y_offset: f32,
 
+    /// Optional Node3D whose global position drives the clip origin (overrides the character group)
+    #[export]
+    pub cross_section_follow_node: NodePath,
+
     // ═══════════════════════════════════════════
     // Grass Toon Lighting (Dylearn-based)
     // ═══════════════════════════════════════════
@@ -302,6 +361,34 @@ pub struct PixyTerrain {
     #[init(val = 3.0)]
     pub flower_light_steps: f32,
 
+    // ═══════════════════════════════════════════
+    // Height Limits
+    // ═══════════════════════════════════════════
+    #[export_group(name = "Limits")]
+    /// Per-vertex height clamps, `Vector2i(terrain-wide vertex) -> Vector2(min, max)`.
+    /// Edit with `set_height_limit` or the Paint Limits brush. Brush strokes and `carve_river` clamp
+    /// the heights they write; undo restores the previous heights as they were.
+    #[export]
+    pub height_limits: VarDictionary,
+
+    // ═══════════════════════════════════════════
+    // LOD
+    // ═══════════════════════════════════════════
+    #[export_group(name = "LOD")]
+    /// Ascending camera distances at which chunks drop to the next coarser mesh (each level
+    /// doubles the cell stride). Empty disables LOD. Runtime only; the editor stays full-res.
+    #[export]
+    pub lod_distances: PackedFloat32Array,
+
+    // ═══════════════════════════════════════════
+    // Debug
+    // ═══════════════════════════════════════════
+    #[export_group(name = "Debug")]
+    /// Briefly outline the chunks re-meshed by each brush commit (`apply_composite_pattern`)
+    #[export]
+    #[init(val = false)]
+    pub debug_draw_affected_chunks: bool,
+
     // ═══════════════════════════════════════════
     // Internal State (not exported)
     // ═══════════════════════════════════════════
@@ -310,6 +397,50 @@ pub struct PixyTerrain {
     pub grass_quad_mesh: Option<Gd<Mesh>>,
     pub is_batch_updating: bool,
 
+    /// Script-provided `(world_x, world_z) -> height` used instead of `noise_hmap` for new chunks.
+    custom_height_source: Option<Callable>,
+
+    /// Runtime-only XZ rectangle (min, max) limiting which chunks get colliders.
+    collision_region: Option<(Vector2, Vector2)>,
+
+    /// Runtime-only: chunks show `wireframe_material` instead of the terrain material.
+    debug_wireframe: bool,
+    wireframe_material: Option<Gd<ShaderMaterial>>,
+
+    /// Runtime-only: chunks hidden with `set_chunk_visible`/`hide_chunks_above`, re-applied
+    /// when undo/redo re-adds a chunk node.
+    hidden_chunks: HashSet<[i32; 2]>,
+
+    /// Runtime-only low-resolution collider built by `build_collision_proxy`.
+    collision_proxy: Option<Gd<StaticBody3D>>,
+
+    /// Runtime-only outline of the last commit's chunks; `affected_outline_time` counts down the fade.
+    affected_outline: Option<Gd<MeshInstance3D>>,
+    affected_outline_material: Option<Gd<StandardMaterial3D>>,
+    affected_outline_time: f32,
+
+    /// Runtime-only brush mask: terrain-wide vertex coords (`chunk * (dim - 1) + cell`).
+    selection: Option<HashSet<[i32; 2]>>,
+
+    /// Runtime-only terrain-local XZ rectangle (min, max) brushes may not edit outside of.
+    edit_region: Option<(Vector2, Vector2)>,
+
+    /// Set by `set_noise_amplitude(_, true)`; noise heights are re-baked once in the next `process`.
+    noise_rebake_pending: bool,
+
+    /// Chunks edited by a large composite pattern, waiting to be re-meshed in `process`.
+    remesh_queue: VecDeque<[i32; 2]>,
+
+    /// Runtime-only `update_chunk_lods` state. `lod_keys` caches the sorted chunk keys and is
+    /// reset to `None` whenever chunks are added, removed or resized, which forces a new pass.
+    /// The camera position and thresholds are those of the last pass; `lod_active` means it
+    /// left a chunk above LOD 0, `lod_pending` that it ran out of re-mesh budget.
+    lod_keys: Option<Vec<[i32; 2]>>,
+    lod_camera: Option<Vector3>,
+    lod_thresholds: Vec<f32>,
+    lod_active: bool,
+    lod_pending: bool,
+
     #[init(val = HashMap::new())]
     chunks: HashMap<[i32; 2], Gd<PixyTerrainChunk>>,
 }
@@ -320,7 +451,19 @@ impl INode3D for PixyTerrain {
         self.base_mut().call_deferred("_deferred_enter_tree", &[]);
     }
 
-    fn process(&mut self, _delta: f64) {
+    fn exit_tree(&mut self) {
+        self.dispose();
+    }
+
+    fn process(&mut self, delta: f64) {
+        if self.noise_rebake_pending {
+            self.noise_rebake_pending = false;
+            self.rebake_noise_heights();
+        }
+        self.drain_remesh_queue();
+        self.update_chunk_lods();
+        self.fade_affected_outline(delta as f32);
+
         // Character tracking: collect positions from group, push to grass material
         if self.character_displacement_enabled {
             if let Some(mut tree) = self.base().get_tree() {
@@ -355,7 +498,14 @@ impl INode3D for PixyTerrain {
 
         // Cross-section: clip terrain above the player from the camera's perspective
         if self.cross_section_enabled {
-            if let Some(mut tree) = self.base().get_tree() {
+            if !self.cross_section_follow_node.is_empty() {
+                let path = self.cross_section_follow_node.clone();
+                if let Some(node) = self.base().get_node_or_null(&path) {
+                    if let Ok(node3d) = node.try_cast::<Node3D>() {
+                        self.set_clip_from_node(node3d);
+                    }
+                }
+            } else if let Some(mut tree) = self.base().get_tree() {
                 let group_name = StringName::from(&self.character_group_name);
                 let nodes = tree.get_nodes_in_group(&group_name);
                 if let Some(node) = nodes.get(0) {
@@ -366,14 +516,7 @@ impl INode3D for PixyTerrain {
                             player_pos.y + self.cross_section_y_offset,
                             player_pos.z,
                         );
-                        if let Some(ref mut mat) = self.terrain_material {
-                            mat.set_shader_parameter("cross_section_enabled", &true.to_variant());
-                            mat.set_shader_parameter("clip_origin", &clip_origin.to_variant());
-                        }
-                        if let Some(ref mut mat) = self.grass_material {
-                            mat.set_shader_parameter("cross_section_enabled", &true.to_variant());
-                            mat.set_shader_parameter("clip_origin", &clip_origin.to_variant());
-                        }
+                        self.set_clip_origin(clip_origin);
                     }
                 }
             }
@@ -395,6 +538,14 @@ impl PixyTerrain {
         self.refresh_grass_mesh();
     }
 
+    /// Set the cross-section clip origin from a node's global position.
+    /// Useful for animating a "cutting plane" node; the plane itself still faces the camera.
+    #[func]
+    pub fn set_clip_from_node(&mut self, node: Gd<Node3D>) {
+        let clip_origin = node.get_global_position();
+        self.set_clip_origin(clip_origin);
+    }
+
     #[func]
     fn _deferred_enter_tree(&mut self) {
         // Register fallback global shader parameters (no-ops if already present)
@@ -408,6 +559,7 @@ impl PixyTerrain {
 
         // Discover existing chunk children
         self.chunks.clear();
+        self.lod_keys = None;
         let children = self.base().get_children();
         for i in 0..children.len() {
             let Some(child): Option<Gd<Node>> = children.get(i) else {
@@ -424,10 +576,10 @@ impl PixyTerrain {
         let grass_config = self.make_grass_config();
         let flower_config = self.make_flower_config();
         let noise = self.noise_hmap.clone();
-        let material = self.terrain_material.clone();
+        let material = self.chunk_material();
 
         // Initialize all discovered chunks with cached configs
-        let chunk_keys: Vec<[i32; 2]> = self.chunks.keys().cloned().collect();
+        let chunk_keys: Vec<[i32; 2]> = sorted_chunk_keys(&self.chunks);
         for key in chunk_keys {
             if let Some(chunk) = self.chunks.get(&key) {
                 let mut chunk = chunk.clone();
@@ -615,6 +767,53 @@ impl PixyTerrain {
         );
     }
 
+    /// Recreate the terrain material from the shader and current exports, then
+    /// reassign it to every chunk without re-meshing.
+    #[func]
+    pub fn rebuild_materials(&mut self) {
+        self.terrain_material = None;
+        self.ensure_terrain_material();
+        self.force_batch_update();
+        self.assign_chunk_materials();
+    }
+
+    /// Show every chunk (including ones added later) with an unshaded wireframe material,
+    /// or switch back to the terrain material. Geometry is not regenerated.
+    #[func]
+    pub fn set_debug_wireframe(&mut self, enabled: bool) {
+        self.debug_wireframe = enabled;
+        self.assign_chunk_materials();
+    }
+
+    #[func]
+    pub fn is_debug_wireframe(&self) -> bool {
+        self.debug_wireframe
+    }
+
+    /// Material chunks should render with: the wireframe in debug mode, else the terrain material.
+    fn chunk_material(&mut self) -> Option<Gd<ShaderMaterial>> {
+        if !self.debug_wireframe {
+            return self.terrain_material.clone();
+        }
+        if self.wireframe_material.is_none() {
+            let mut shader = Shader::new_gd();
+            shader.set_code(WIREFRAME_SHADER_CODE);
+            let mut mat = ShaderMaterial::new_gd();
+            mat.set_shader(&shader);
+            self.wireframe_material = Some(mat);
+        }
+        self.wireframe_material.clone()
+    }
+
+    fn assign_chunk_materials(&mut self) {
+        let material = self.chunk_material();
+        for key in sorted_chunk_keys(&self.chunks) {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                chunk.bind_mut().set_terrain_material(material.clone());
+            }
+        }
+    }
+
     /// Sync all shader parameters from terrain exports to the terrain material.
     #[func]
     pub fn force_batch_update(&mut self) {
@@ -672,6 +871,7 @@ impl PixyTerrain {
     }
 
     /// Sync all grass shader parameters from terrain fields to the shared grass material.
+    #[func]
     pub fn force_grass_material_update(&mut self) {
         use crate::shader_sync::*;
 
@@ -765,7 +965,7 @@ impl PixyTerrain {
 
         // Propagate the new mesh to every chunk's grass MultiMesh
         if let Some(ref new_mesh) = self.grass_quad_mesh {
-            let keys: Vec<[i32; 2]> = self.chunks.keys().cloned().collect();
+            let keys: Vec<[i32; 2]> = sorted_chunk_keys(&self.chunks);
             for key in keys {
                 if let Some(chunk) = self.chunks.get(&key) {
                     let chunk = chunk.clone();
@@ -857,6 +1057,17 @@ impl PixyTerrain {
         ]
     }
 
+    fn set_clip_origin(&mut self, clip_origin: Vector3) {
+        if let Some(ref mut mat) = self.terrain_material {
+            mat.set_shader_parameter("cross_section_enabled", &true.to_variant());
+            mat.set_shader_parameter("clip_origin", &clip_origin.to_variant());
+        }
+        if let Some(ref mut mat) = self.grass_material {
+            mat.set_shader_parameter("cross_section_enabled", &true.to_variant());
+            mat.set_shader_parameter("clip_origin", &clip_origin.to_variant());
+        }
+    }
+
     fn make_shared_params(&self) -> SharedTerrainParams {
         SharedTerrainParams {
             dimensions: self.dimensions,
@@ -876,6 +1087,12 @@ impl PixyTerrain {
         TerrainConfig {
             shared: self.make_shared_params(),
             extra_collision_layer: self.extra_collision_layer,
+            generate_collision: self.generate_collision,
+            collision_region: self.collision_region,
+            noise_amplitude: self.noise_amplitude,
+            terrace_height: self.terrace_height,
+            terrace_sharpness: self.terrace_sharpness,
+            degenerate_area_epsilon: self.degenerate_area_epsilon,
         }
     }
 
@@ -925,10 +1142,119 @@ impl PixyTerrain {
     #[func]
     pub fn clear(&mut self) {
         godot_print!("PixyTerrain: clear()");
-        let keys: Vec<[i32; 2]> = self.chunks.keys().cloned().collect();
+        let keys: Vec<[i32; 2]> = sorted_chunk_keys(&self.chunks);
         for key in keys {
             self.remove_chunk(key[0], key[1]);
         }
+        self.remesh_queue.clear();
+    }
+
+    /// Write every chunk's heights, color maps, grass mask and merge mode, plus the grid
+    /// `dimensions` and `cell_size`, to one versioned file (Godot `store_var` format).
+    /// Shading, texture and noise settings stay in the scene. Returns false on I/O errors.
+    #[func]
+    pub fn save_terrain(&mut self, path: GString) -> bool {
+        let mut chunks = VarArray::new();
+        for key in sorted_chunk_keys(&self.chunks) {
+            let Some(chunk) = self.chunks.get_mut(&key) else {
+                continue;
+            };
+            let mut bind = chunk.bind_mut();
+            bind.sync_to_packed();
+            let mut entry = VarDictionary::new();
+            entry.set("coords", Vector2i::new(key[0], key[1]));
+            entry.set("merge_mode", bind.merge_mode);
+            entry.set("height", bind.saved_height_map.clone());
+            entry.set("color_0", bind.saved_color_map_0.clone());
+            entry.set("color_1", bind.saved_color_map_1.clone());
+            entry.set("wall_color_0", bind.saved_wall_color_map_0.clone());
+            entry.set("wall_color_1", bind.saved_wall_color_map_1.clone());
+            entry.set("grass_mask", bind.saved_grass_mask_map.clone());
+            chunks.push(&entry.to_variant());
+        }
+
+        let mut data = VarDictionary::new();
+        data.set("version", TERRAIN_FILE_VERSION);
+        data.set("dimensions", self.dimensions);
+        data.set("cell_size", self.cell_size);
+        data.set("chunks", chunks.clone());
+
+        let Some(mut file) = FileAccess::open(&path, ModeFlags::WRITE) else {
+            godot_error!("PixyTerrain: cannot open {path} for writing");
+            return false;
+        };
+        file.store_var(&data.to_variant());
+        file.close();
+        godot_print!("PixyTerrain: Saved {} chunks to {path}", chunks.len());
+        true
+    }
+
+    /// Replace all chunks with the contents of a `save_terrain` file, adopting its
+    /// `dimensions` and `cell_size`. Not undoable. Returns false (and leaves the terrain
+    /// untouched) if the file is missing or not a terrain file of this version.
+    #[func]
+    pub fn load_terrain(&mut self, path: GString) -> bool {
+        let Some(mut file) = FileAccess::open(&path, ModeFlags::READ) else {
+            godot_error!("PixyTerrain: cannot open {path}");
+            return false;
+        };
+        let data = file.get_var();
+        file.close();
+        let Ok(data) = data.try_to::<VarDictionary>() else {
+            godot_error!("PixyTerrain: {path} is not a terrain file");
+            return false;
+        };
+        let version = dict_field::<i32>(&data, "version");
+        if version != Some(TERRAIN_FILE_VERSION) {
+            godot_error!("PixyTerrain: {path} has unsupported version {version:?}");
+            return false;
+        }
+        let (Some(dimensions), Some(cell_size), Some(chunks)) = (
+            dict_field::<Vector3i>(&data, "dimensions"),
+            dict_field::<Vector2>(&data, "cell_size"),
+            dict_field::<VarArray>(&data, "chunks"),
+        ) else {
+            godot_error!("PixyTerrain: {path} is missing terrain data");
+            return false;
+        };
+
+        self.clear();
+        self.clear_selection();
+        self.dimensions = dimensions;
+        self.cell_size = cell_size;
+        self.ensure_terrain_material();
+        self.ensure_grass_material();
+        self.force_batch_update();
+
+        for entry in chunks.iter_shared() {
+            let Ok(entry) = entry.try_to::<VarDictionary>() else {
+                continue;
+            };
+            let Some(coords) = dict_field::<Vector2i>(&entry, "coords") else {
+                continue;
+            };
+            let packed_colors =
+                |name: &str| dict_field::<PackedColorArray>(&entry, name).unwrap_or_default();
+
+            let mut chunk = Gd::<PixyTerrainChunk>::from_init_fn(PixyTerrainChunk::new_with_base);
+            chunk.set_name(&format!("Chunk ({}, {})", coords.x, coords.y));
+            {
+                let mut bind = chunk.bind_mut();
+                bind.chunk_coords = coords;
+                bind.merge_mode = dict_field(&entry, "merge_mode").unwrap_or(self.merge_mode);
+                bind.saved_height_map = dict_field(&entry, "height").unwrap_or_default();
+                bind.saved_color_map_0 = packed_colors("color_0");
+                bind.saved_color_map_1 = packed_colors("color_1");
+                bind.saved_wall_color_map_0 = packed_colors("wall_color_0");
+                bind.saved_wall_color_map_1 = packed_colors("wall_color_1");
+                bind.saved_grass_mask_map = packed_colors("grass_mask");
+            }
+            // initialize_terrain restores the packed arrays and meshes the chunk
+            self.add_chunk_internal(coords, chunk, true);
+        }
+        let count = self.chunks.len();
+        godot_print!("PixyTerrain: Loaded {count} chunks from {path}");
+        true
     }
 
     /// Check if a chunk exists at the given coordinates.
@@ -942,7 +1268,9 @@ impl PixyTerrain {
     pub fn remove_chunk(&mut self, x: i32, z: i32) {
         if let Some(mut chunk) = self.chunks.remove(&[x, z]) {
             chunk.queue_free();
+            self.lod_keys = None;
         }
+        self.hidden_chunks.remove(&[x, z]);
     }
 
     /// Remove a chunk from the tree without freeing it (for undo/redo).
@@ -951,6 +1279,7 @@ impl PixyTerrain {
         if let Some(mut chunk) = self.chunks.remove(&[x, z]) {
             self.base_mut().remove_child(&chunk);
             chunk.set_owner(Gd::null_arg());
+            self.lod_keys = None;
         }
     }
 
@@ -960,141 +1289,894 @@ impl PixyTerrain {
         self.chunks.get(&[x, z]).cloned()
     }
 
-    /// Get all chunk coordinate keys as a PackedVector2Array.
+    /// Get all chunk coordinate keys as a PackedVector2Array, sorted by (x, z).
     #[func]
     pub fn get_chunk_keys(&self) -> PackedVector2Array {
         let mut arr = PackedVector2Array::new();
-        for key in self.chunks.keys() {
+        for key in sorted_chunk_keys(&self.chunks) {
             arr.push(Vector2::new(key[0] as f32, key[1] as f32));
         }
         arr
     }
 
-    /// Get the merge threshold for the current merge mode.
+    /// Show or hide one chunk without unloading it, for cutaway views and debugging.
+    /// The chunk keeps its data and collider; the hidden state survives undo/redo re-adds.
     #[func]
-    pub fn get_merge_threshold(&self) -> f32 {
-        MergeMode::from_index(self.merge_mode).threshold()
+    pub fn set_chunk_visible(&mut self, coords: Vector2i, visible: bool) {
+        let key = [coords.x, coords.y];
+        let Some(mut chunk) = self.chunks.get(&key).cloned() else {
+            godot_warn!(
+                "PixyTerrain: set_chunk_visible: no chunk at ({}, {})",
+                coords.x,
+                coords.y
+            );
+            return;
+        };
+        if visible {
+            self.hidden_chunks.remove(&key);
+        } else {
+            self.hidden_chunks.insert(key);
+        }
+        chunk.set_visible(visible);
     }
 
-    /// Create a new chunk at the given chunk coordinates, copying shared edges from neighbors.
+    /// Hide every chunk lying entirely above terrain-local height `y` (its lowest vertex is
+    /// higher), exposing what's underneath. Returns how many chunks were hidden.
     #[func]
-    pub fn add_new_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
-        // Ensure material exists (may not yet if called before _deferred_enter_tree)
-        self.ensure_terrain_material();
-        self.ensure_grass_material();
-        self.force_batch_update();
-
-        let chunk_coords = Vector2i::new(chunk_x, chunk_z);
-        let mut new_chunk = Gd::<PixyTerrainChunk>::from_init_fn(PixyTerrainChunk::new_with_base);
+    pub fn hide_chunks_above(&mut self, y: f32) -> i32 {
+        let bounds: Vec<([i32; 2], Aabb)> = sorted_chunk_keys(&self.chunks)
+            .into_iter()
+            .map(|key| (key, self.get_chunk_aabb(Vector2i::new(key[0], key[1]))))
+            .collect();
+        let above = chunks_above(&bounds, y);
+        for key in &above {
+            self.set_chunk_visible(Vector2i::new(key[0], key[1]), false);
+        }
+        above.len() as i32
+    }
 
-        new_chunk.set_name(&format!("Chunk ({}, {})", chunk_x, chunk_z));
-        {
-            let mut chunk_bind = new_chunk.bind_mut();
-            chunk_bind.chunk_coords = chunk_coords;
-            chunk_bind.merge_mode = self.merge_mode;
+    /// Make every chunk hidden by `set_chunk_visible`/`hide_chunks_above` visible again.
+    #[func]
+    pub fn show_all_chunks(&mut self) {
+        for key in std::mem::take(&mut self.hidden_chunks) {
+            if let Some(mut chunk) = self.chunks.get(&key).cloned() {
+                chunk.set_visible(true);
+            }
         }
+    }
 
-        // Add to tree and initialize
-        self.add_chunk_internal(chunk_coords, new_chunk.clone(), false);
+    /// Whether the chunk at `coords` was hidden with `set_chunk_visible`/`hide_chunks_above`.
+    #[func]
+    pub fn is_chunk_hidden(&self, coords: Vector2i) -> bool {
+        self.hidden_chunks.contains(&[coords.x, coords.y])
+    }
 
-        // Copy shared edges from adjacent chunks
-        let dim = self.dimensions;
+    /// Build one coarse trimesh collider over all loaded chunks, sampling heights every
+    /// `proxy_cell_size` units, replacing any previous proxy. Cheaper than per-chunk colliders
+    /// for gameplay that doesn't need exact collision (turn off `generate_collision`). It sits
+    /// only on `extra_collision_layer`, so editor brush raycasts still hit the exact chunk
+    /// colliders. Not rebuilt on edits; returns the triangle count.
+    #[func]
+    pub fn build_collision_proxy(&mut self, proxy_cell_size: f32) -> i32 {
+        self.clear_collision_proxy();
+        if self.chunks.is_empty() || proxy_cell_size <= 0.0 {
+            return 0;
+        }
 
-        // Left neighbor: copy rightmost column → new chunk leftmost column
-        if let Some(left) = self.chunks.get(&[chunk_x - 1, chunk_z]).cloned() {
-            let left_bind = left.bind();
-            let mut new_bind = new_chunk.bind_mut();
-            for z in 0..dim.z {
-                if let Some(h) = left_bind.get_height_at(dim.x - 1, z) {
-                    new_bind.set_height_at(0, z, h);
-                }
-            }
+        let keys = sorted_chunk_keys(&self.chunks);
+        let mut min = Vector2::new(f32::INFINITY, f32::INFINITY);
+        let mut max = Vector2::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for key in &keys {
+            let aabb = self.get_chunk_aabb(Vector2i::new(key[0], key[1]));
+            min = min.coord_min(Vector2::new(aabb.position.x, aabb.position.z));
+            max = max.coord_max(Vector2::new(aabb.end().x, aabb.end().z));
+        }
+        let xs = proxy_axis(min.x, max.x, proxy_cell_size);
+        let zs = proxy_axis(min.y, max.y, proxy_cell_size);
+        let faces = grid_triangles(&xs, &zs, |x, z| {
+            proxy_height(x, z, self.dimensions, self.cell_size, |key, [cx, cz]| {
+                self.chunks.get(&key)?.bind().get_height_at(cx, cz)
+            })
+        });
+        if faces.is_empty() {
+            return 0;
         }
 
-        // Right neighbor
-        if let Some(right) = self.chunks.get(&[chunk_x + 1, chunk_z]).cloned() {
-            let right_bind = right.bind();
-            let mut new_bind = new_chunk.bind_mut();
-            for z in 0..dim.z {
-                if let Some(h) = right_bind.get_height_at(0, z) {
-                    new_bind.set_height_at(dim.x - 1, z, h);
-                }
+        let mut shape = ConcavePolygonShape3D::new_gd();
+        shape.set_faces(&PackedVector3Array::from(faces.as_slice()));
+        shape.set_backface_collision_enabled(true);
+        let mut shape_node = CollisionShape3D::new_alloc();
+        shape_node.set_shape(&shape);
+        let mut body = StaticBody3D::new_alloc();
+        body.set_name("CollisionProxy");
+        body.set_collision_layer(0);
+        if (1..=32).contains(&self.extra_collision_layer) {
+            body.set_collision_layer_value(self.extra_collision_layer, true);
+        }
+        body.add_child(&shape_node);
+        // No owner: the proxy is never saved with the scene
+        self.base_mut().add_child(&body);
+        self.collision_proxy = Some(body);
+        (faces.len() / 3) as i32
+    }
+
+    /// Remove the collider made by `build_collision_proxy`, if any.
+    #[func]
+    pub fn clear_collision_proxy(&mut self) {
+        if let Some(mut body) = self.collision_proxy.take() {
+            if body.is_instance_valid() {
+                body.queue_free();
             }
         }
+    }
 
-        // Up neighbor: copy bottom row → new chunk top row
-        if let Some(up) = self.chunks.get(&[chunk_x, chunk_z - 1]).cloned() {
-            let up_bind = up.bind();
-            let mut new_bind = new_chunk.bind_mut();
-            for x in 0..dim.x {
-                if let Some(h) = up_bind.get_height_at(x, dim.z - 1) {
-                    new_bind.set_height_at(x, 0, h);
-                }
+    /// Release all runtime-only state: chunk handles, queued re-meshes and noise re-bake, the
+    /// collision proxy and affected-chunk outline nodes, debug materials, hidden chunks, the
+    /// selection, edit and collision regions, and the custom height source (whose Callable
+    /// may keep its target alive). Chunk nodes stay in the scene since they are saved with
+    /// it; `_deferred_enter_tree` rediscovers them. Called from `exit_tree`; safe to repeat.
+    #[func]
+    pub fn dispose(&mut self) {
+        self.clear_collision_proxy();
+        if let Some(mut outline) = self.affected_outline.take() {
+            if outline.is_instance_valid() {
+                outline.queue_free();
             }
         }
+        self.affected_outline_material = None;
+        self.affected_outline_time = 0.0;
+        self.wireframe_material = None;
+        self.custom_height_source = None;
+        self.hidden_chunks.clear();
+        self.selection = None;
+        self.edit_region = None;
+        self.collision_region = None;
+        self.noise_rebake_pending = false;
+        self.remesh_queue.clear();
+        self.chunks.clear();
+        self.lod_keys = None;
+        self.lod_camera = None;
+        self.lod_thresholds.clear();
+        self.lod_active = false;
+        self.lod_pending = false;
+    }
 
-        // Down neighbor
-        if let Some(down) = self.chunks.get(&[chunk_x, chunk_z + 1]).cloned() {
-            let down_bind = down.bind();
-            let mut new_bind = new_chunk.bind_mut();
-            for x in 0..dim.x {
-                if let Some(h) = down_bind.get_height_at(x, 0) {
-                    new_bind.set_height_at(x, dim.z - 1, h);
-                }
+    /// Freeze the terrain: a new `Node3D` (not added to the tree) holding one plain
+    /// `MeshInstance3D` per chunk with its full-resolution mesh, position and terrain material.
+    /// Each mesh is built fresh from the chunk's heights and colours (one uncached mesh
+    /// build per chunk), so chunks keep their current LOD and displayed mesh.
+    /// Children are owned by the returned node, so `PackedScene.pack()` keeps them.
+    /// Grass, flowers and colliders are not included.
+    #[func]
+    pub fn bake_to_scene(&self) -> Gd<Node3D> {
+        let mut root = Node3D::new_alloc();
+        root.set_name("PixyTerrainBake");
+        root.set_transform(self.base().get_transform());
+        for (key, name, position) in bake_layout(&self.chunks, self.dimensions, self.cell_size) {
+            let Some(mesh) = self.chunks[&key].bind().build_full_res_mesh() else {
+                continue;
+            };
+            let mut instance = MeshInstance3D::new_alloc();
+            instance.set_name(&name);
+            instance.set_mesh(&mesh);
+            instance.set_position(position);
+            if let Some(material) = self.terrain_material.clone() {
+                instance.set_surface_override_material(0, &material.upcast::<Material>());
             }
+            root.add_child(&instance);
+            instance.set_owner(&root);
         }
+        root
+    }
 
-        // Generate mesh
-        new_chunk.bind_mut().regenerate_mesh();
+    /// Only build colliders for chunks whose XZ center lies inside [min_xz, max_xz].
+    #[func]
+    pub fn set_collision_region(&mut self, min_xz: Vector2, max_xz: Vector2) {
+        let min = Vector2::new(min_xz.x.min(max_xz.x), min_xz.y.min(max_xz.y));
+        let max = Vector2::new(min_xz.x.max(max_xz.x), min_xz.y.max(max_xz.y));
+        self.collision_region = Some((min, max));
+        self.refresh_chunk_collision();
     }
 
-    fn add_chunk_internal(
-        &mut self,
-        coords: Vector2i,
-        mut chunk: Gd<PixyTerrainChunk>,
-        regenerate: bool,
-    ) {
-        let terrain_config = self.make_terrain_config();
-        let grass_config = self.make_grass_config();
-        let flower_config = self.make_flower_config();
-        let noise = self.noise_hmap.clone();
-        let material = self.terrain_material.clone();
+    /// Remove the collision region so every chunk gets a collider again.
+    #[func]
+    pub fn clear_collision_region(&mut self) {
+        self.collision_region = None;
+        self.refresh_chunk_collision();
+    }
 
-        self.chunks.insert([coords.x, coords.y], chunk.clone());
+    /// Restrict brush edits to these vertices, in terrain-wide grid coords
+    /// (`chunk * (dimensions - 1) + cell`). An empty array blocks every edit.
+    #[func]
+    pub fn set_selection(&mut self, cells: Array<Vector2i>) {
+        self.selection = Some(cells.iter_shared().map(|c| [c.x, c.y]).collect());
+    }
 
-        {
-            let mut chunk_bind = chunk.bind_mut();
-            chunk_bind.chunk_coords = coords;
-            chunk_bind.set_terrain_config(terrain_config);
-        }
+    /// Remove the selection so brushes can edit everywhere again.
+    #[func]
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+    }
 
-        self.base_mut().add_child(&chunk);
+    #[func]
+    pub fn has_selection(&self) -> bool {
+        self.selection.is_some()
+    }
 
-        // Position the chunk in world space
+    /// Selected vertices, sorted by (x, z). Empty when there is no selection.
+    #[func]
+    pub fn get_selection(&self) -> Array<Vector2i> {
+        let mut arr = Array::new();
+        if let Some(selection) = &self.selection {
+            let mut cells: Vec<[i32; 2]> = selection.iter().copied().collect();
+            cells.sort_unstable();
+            for c in cells {
+                arr.push(Vector2i::new(c[0], c[1]));
+            }
+        }
+        arr
+    }
+
+    /// Select every vertex of the loaded chunks that is not currently selected.
+    /// With no selection this selects all of them.
+    #[func]
+    pub fn invert_selection(&mut self) {
         let dim = self.dimensions;
-        let cell = self.cell_size;
-        let pos = Vector3::new(
-            coords.x as f32 * ((dim.x - 1) as f32 * cell.x),
-            0.0,
-            coords.y as f32 * ((dim.z - 1) as f32 * cell.y),
-        );
-        chunk.set_position(pos);
+        let all: HashSet<[i32; 2]> = self
+            .chunks
+            .keys()
+            .flat_map(|&key| chunk_vertices(key, dim))
+            .collect();
+        let current = self.selection.take().unwrap_or_default();
+        self.selection = Some(all.difference(&current).copied().collect());
+    }
 
-        // Set owner for editor persistence
-        if Engine::singleton().is_editor_hint() {
-            if let Some(mut editor) = Engine::singleton().get_singleton("EditorInterface") {
-                let scene_root = editor.call("get_edited_scene_root", &[]);
-                if let Ok(root) = scene_root.try_to::<Gd<Node>>() {
-                    Self::set_owner_recursive(&mut chunk.clone().upcast::<Node>(), &root);
-                }
-            }
+    /// Dilate the selection by `n` vertices (Manhattan distance).
+    #[func]
+    pub fn grow_selection(&mut self, n: i32) {
+        if let Some(selection) = self.selection.as_mut() {
+            *selection = grow_cells(selection, n.max(0));
         }
+    }
 
-        chunk
-            .bind_mut()
-            .initialize_terrain(regenerate, noise, material, grass_config, flower_config);
+    /// Erode the selection by `n` vertices (Manhattan distance).
+    #[func]
+    pub fn shrink_selection(&mut self, n: i32) {
+        if let Some(selection) = self.selection.as_mut() {
+            *selection = shrink_cells(selection, n.max(0));
+        }
+    }
 
-        godot_print!("PixyTerrain: Added chunk at ({}, {})", coords.x, coords.y);
+    pub fn selection(&self) -> Option<&HashSet<[i32; 2]>> {
+        self.selection.as_ref()
+    }
+
+    /// Restrict brush edits to vertices inside the terrain-local XZ rectangle [min_xz, max_xz].
+    /// Combines with the selection: a vertex must pass both to be edited.
+    #[func]
+    pub fn set_edit_region(&mut self, min_xz: Vector2, max_xz: Vector2) {
+        let min = Vector2::new(min_xz.x.min(max_xz.x), min_xz.y.min(max_xz.y));
+        let max = Vector2::new(min_xz.x.max(max_xz.x), min_xz.y.max(max_xz.y));
+        self.edit_region = Some((min, max));
+    }
+
+    /// Keep brush edits of a terrain-wide vertex (`chunk * (dimensions - 1) + cell`) within
+    /// [min_height, max_height]. Use `-INF`/`INF` for a one-sided limit. Heights already out of
+    /// range are left alone until the next edit.
+    #[func]
+    pub fn set_height_limit(&mut self, vertex: Vector2i, min_height: f32, max_height: f32) {
+        let limit = Vector2::new(min_height.min(max_height), min_height.max(max_height));
+        self.height_limits.set(vertex, limit);
+    }
+
+    #[func]
+    pub fn clear_height_limit(&mut self, vertex: Vector2i) {
+        self.height_limits.remove(vertex);
+    }
+
+    #[func]
+    pub fn clear_height_limits(&mut self) {
+        self.height_limits.clear();
+    }
+
+    /// `height` clamped to the vertex's limit, if it has one.
+    pub fn limit_height(&self, vertex: Vector2i, height: f32) -> f32 {
+        match self
+            .height_limits
+            .get(vertex)
+            .and_then(|v| v.try_to::<Vector2>().ok())
+        {
+            Some(limit) => clamp_to_limit(height, limit),
+            None => height,
+        }
+    }
+
+    /// Clamp a brush's `chunk -> cell -> height` do-pattern to the vertex limits, in place.
+    /// Only the do side goes through here, so undo restores out-of-limit heights exactly.
+    pub fn limit_pattern_heights(&self, heights: &VarDictionary) {
+        if self.height_limits.is_empty() {
+            return;
+        }
+        for (chunk, cells) in heights.iter_shared() {
+            let chunk: Vector2i = chunk.to();
+            let mut cells: VarDictionary = cells.to();
+            let entries: Vec<(Vector2i, f32)> = cells
+                .iter_shared()
+                .map(|(k, v)| (k.to::<Vector2i>(), v.to::<f32>()))
+                .collect();
+            for (cell, h) in entries {
+                let [gx, gz] = global_vertex([chunk.x, chunk.y], [cell.x, cell.y], self.dimensions);
+                cells.set(cell, self.limit_height(Vector2i::new(gx, gz), h));
+            }
+        }
+    }
+
+    /// Remove the edit region so brushes can edit everywhere again.
+    #[func]
+    pub fn clear_edit_region(&mut self) {
+        self.edit_region = None;
+    }
+
+    pub fn edit_region(&self) -> Option<(Vector2, Vector2)> {
+        self.edit_region
+    }
+
+    /// Push the current collision settings to all chunks and rebuild their colliders.
+    fn refresh_chunk_collision(&mut self) {
+        let terrain_config = self.make_terrain_config();
+        for chunk in self.chunks.values() {
+            let mut chunk = chunk.clone();
+            let mut bind = chunk.bind_mut();
+            bind.set_terrain_config(terrain_config.clone());
+            bind.rebuild_collision();
+        }
+    }
+
+    /// Bounds of every loaded chunk as `{ "coords": Vector2i, "aabb": AABB }` dictionaries.
+    /// AABBs are in terrain-local space; Y spans each chunk's heightmap range.
+    #[func]
+    pub fn get_loaded_chunk_bounds(&self) -> VarArray {
+        let mut result = VarArray::new();
+        for key in sorted_chunk_keys(&self.chunks) {
+            let Some(chunk) = self.chunks.get(&key) else {
+                continue;
+            };
+            let mut entry = VarDictionary::new();
+            entry.set("coords", Vector2i::new(key[0], key[1]));
+            entry.set("aabb", chunk.bind().get_chunk_bounds());
+            result.push(&entry.to_variant());
+        }
+        result
+    }
+
+    /// Terrain-local bounds of the chunk at `coords`, loaded or not. Loaded chunks span
+    /// their heightmap range in Y; others span `0..dimensions.y`.
+    #[func]
+    pub fn get_chunk_aabb(&self, coords: Vector2i) -> Aabb {
+        if let Some(chunk) = self.chunks.get(&[coords.x, coords.y]) {
+            return chunk.bind().get_chunk_bounds();
+        }
+        chunk_aabb(
+            coords,
+            self.dimensions,
+            self.cell_size,
+            0.0,
+            self.dimensions.y as f32,
+        )
+    }
+
+    /// Change the number of heightmap vertices per chunk side while keeping chunk footprints
+    /// and edits: heights are resampled bilinearly, color maps by nearest vertex, and
+    /// `cell_size` is scaled to match. Every chunk is remeshed; the selection is cleared.
+    #[func]
+    pub fn set_resolution(&mut self, vertices_per_side: i32) {
+        let old = self.dimensions;
+        let n = vertices_per_side.clamp(3, 257);
+        if n == old.x && n == old.z {
+            return;
+        }
+
+        for key in sorted_chunk_keys(&self.chunks) {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                chunk.bind_mut().resample_grid(n as usize, n as usize);
+            }
+        }
+
+        self.cell_size = Vector2::new(
+            self.cell_size.x * (old.x - 1) as f32 / (n - 1) as f32,
+            self.cell_size.y * (old.z - 1) as f32 / (n - 1) as f32,
+        );
+        self.dimensions = Vector3i::new(n, old.y, n);
+        self.selection = None;
+        self.remesh_queue.clear();
+        self.lod_keys = None;
+
+        let terrain_config = self.make_terrain_config();
+        let grass_config = self.make_grass_config();
+        let flower_config = self.make_flower_config();
+        let noise = self.noise_hmap.clone();
+        let material = self.chunk_material();
+        for key in sorted_chunk_keys(&self.chunks) {
+            let Some(chunk) = self.chunks.get(&key) else {
+                continue;
+            };
+            let mut chunk = chunk.clone();
+            let mut bind = chunk.bind_mut();
+            bind.set_terrain_config(terrain_config.clone());
+            bind.sync_to_packed();
+            bind.initialize_terrain(
+                true,
+                noise.clone(),
+                material.clone(),
+                grass_config.clone(),
+                flower_config.clone(),
+            );
+        }
+        godot_print!(
+            "PixyTerrain: Resampled {} chunks to {}x{} vertices",
+            self.chunks.len(),
+            n,
+            n
+        );
+    }
+
+    /// Approximate surface normal (world space) at the heightmap vertex nearest `world_pos`,
+    /// from central differences of neighboring vertex heights. Cliff faces are not resolved:
+    /// a wall reads as a steep slope. Returns `Vector3::UP` outside loaded chunks.
+    #[func]
+    pub fn normal_at(&self, world_pos: Vector3) -> Vector3 {
+        let [gx, gz] = self.nearest_vertex(world_pos);
+        let Some(center) = self.vertex_height(gx, gz) else {
+            return Vector3::UP;
+        };
+        let neighbors = [
+            self.vertex_height(gx - 1, gz),
+            self.vertex_height(gx + 1, gz),
+            self.vertex_height(gx, gz - 1),
+            self.vertex_height(gx, gz + 1),
+        ];
+        let local_normal = heightmap_normal(center, neighbors, self.cell_size);
+        (self.base().get_global_basis() * local_normal).normalized()
+    }
+
+    /// Height of a vertex in terrain-wide grid coords (`chunk * (dimensions - 1) + cell`).
+    fn vertex_height(&self, gx: i32, gz: i32) -> Option<f32> {
+        owned_vertex_height(gx, gz, self.dimensions, |key, [x, z]| {
+            self.chunks.get(&key)?.bind().get_height_at(x, z)
+        })
+    }
+
+    /// World positions on flat ground for placing buildings or spawn points: one per connected
+    /// area where every vertex within `min_radius` slopes less than `max_slope` degrees.
+    /// Each returned point is the qualifying vertex nearest its area's centroid.
+    #[func]
+    pub fn find_flat_areas(&self, min_radius: f32, max_slope: f32) -> Array<Vector3> {
+        let mut heights = HashMap::new();
+        for (key, chunk) in &self.chunks {
+            let bind = chunk.bind();
+            let origin = global_vertex(*key, [0, 0], self.dimensions);
+            for [gx, gz] in chunk_vertices(*key, self.dimensions) {
+                if let Some(h) = bind.get_height_at(gx - origin[0], gz - origin[1]) {
+                    heights.insert([gx, gz], h);
+                }
+            }
+        }
+
+        let mut result = Array::new();
+        for [gx, gz] in flat_area_centers(&heights, self.cell_size, min_radius, max_slope) {
+            let local = Vector3::new(
+                gx as f32 * self.cell_size.x,
+                heights[&[gx, gz]],
+                gz as f32 * self.cell_size.y,
+            );
+            result.push(self.base().to_global(local));
+        }
+        result
+    }
+
+    /// Set the ground texture slot (0-15) of the heightmap vertex nearest `world_pos`, for
+    /// scripted painting without the editor brush. Seam vertices are written in every chunk
+    /// that shares them. Changed chunks join the re-mesh queue, so painting many vertices in
+    /// one frame rebuilds each chunk once. Not undoable. Returns whether anything changed.
+    #[func]
+    pub fn set_texture_at(&mut self, world_pos: Vector3, texture_index: i32) -> bool {
+        if !(0..16).contains(&texture_index) {
+            godot_warn!("PixyTerrain: texture index {texture_index} is outside 0..15");
+            return false;
+        }
+        let (color_0, color_1) = texture_index_to_colors(texture_index);
+        let [gx, gz] = self.nearest_vertex(world_pos);
+        let mut changed = Vec::new();
+        for (key, [x, z]) in vertex_owners(gx, gz, self.dimensions) {
+            let Some(chunk) = self.chunks.get_mut(&key) else {
+                continue;
+            };
+            let mut bind = chunk.bind_mut();
+            let current =
+                TextureIndex::from_color_pair(bind.get_color_0(x, z), bind.get_color_1(x, z));
+            if current.0 as i32 == texture_index {
+                continue;
+            }
+            bind.draw_color_0(x, z, color_0);
+            bind.draw_color_1(x, z, color_1);
+            changed.push(key);
+        }
+        // Re-meshed in `process`, so painting many vertices costs one rebuild per chunk
+        let painted = !changed.is_empty();
+        enqueue_remesh(&mut self.remesh_queue, changed);
+        painted
+    }
+
+    /// Ground texture slot (0-15) of the heightmap vertex nearest `world_pos`, or -1 outside
+    /// loaded chunks.
+    #[func]
+    pub fn get_texture_at(&self, world_pos: Vector3) -> i32 {
+        let [gx, gz] = self.nearest_vertex(world_pos);
+        vertex_owners(gx, gz, self.dimensions)
+            .into_iter()
+            .find_map(|(key, [x, z])| {
+                let bind = self.chunks.get(&key)?.bind();
+                let index =
+                    TextureIndex::from_color_pair(bind.get_color_0(x, z), bind.get_color_1(x, z));
+                Some(index.0 as i32)
+            })
+            .unwrap_or(-1)
+    }
+
+    /// Repaint every vertex using texture slot `from_index` with `to_index`, on ground and
+    /// walls across all chunks, without touching the rest. Returns the chunks re-meshed.
+    #[func]
+    pub fn remap_texture(&mut self, from_index: i32, to_index: i32) -> i32 {
+        let mapping: PackedInt32Array = (0..16)
+            .map(|i| if i == from_index { to_index } else { -1 })
+            .collect();
+        self.remap_textures(mapping)
+    }
+
+    /// Reassign painted texture slots in one pass: vertices using slot `i` get `mapping[i]`.
+    /// Entries of -1 (and slots past the end of `mapping`) are left alone, so swaps work.
+    /// Returns the number of chunks re-meshed; nothing changes if any entry is invalid.
+    #[func]
+    pub fn remap_textures(&mut self, mapping: PackedInt32Array) -> i32 {
+        let mut table: [u8; 16] = std::array::from_fn(|i| i as u8);
+        for (i, &to) in mapping.as_slice().iter().take(16).enumerate() {
+            match to {
+                -1 => {}
+                0..=15 => table[i] = to as u8,
+                _ => {
+                    godot_warn!("PixyTerrain: remap_textures: slot {i} maps to invalid {to}");
+                    return 0;
+                }
+            }
+        }
+
+        let mut changed = 0;
+        for key in sorted_chunk_keys(&self.chunks) {
+            let Some(chunk) = self.chunks.get_mut(&key) else {
+                continue;
+            };
+            let mut bind = chunk.bind_mut();
+            let maps = &mut bind.color_maps;
+            let ground = remap_color_pairs(&mut maps.color_0, &mut maps.color_1, &table);
+            let wall = remap_color_pairs(&mut maps.wall_color_0, &mut maps.wall_color_1, &table);
+            if ground || wall {
+                bind.regenerate_mesh();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// The `[color_0, color_1]` vertex-color pair the terrain shader reads as texture slot
+    /// `idx` (0-15), for scripts that write color maps directly. Empty if out of range.
+    #[func]
+    pub fn texture_index_to_colors(idx: i32) -> PackedColorArray {
+        if !(0..16).contains(&idx) {
+            return PackedColorArray::new();
+        }
+        let (color_0, color_1) = texture_index_to_colors(idx);
+        let mut colors = PackedColorArray::new();
+        colors.push(color_0);
+        colors.push(color_1);
+        colors
+    }
+
+    /// The texture slot (0-15) whose pair is exactly `color_0`/`color_1`; the inverse of
+    /// `texture_index_to_colors`. Returns -1 for any other pair (e.g. blended colors).
+    #[func]
+    pub fn colors_to_texture_index(color_0: Color, color_1: Color) -> i32 {
+        colors_to_texture_index(color_0, color_1)
+    }
+
+    /// Terrain-wide grid coords of the heightmap vertex nearest a world-space position.
+    fn nearest_vertex(&self, world_pos: Vector3) -> [i32; 2] {
+        let local = self.base().to_local(world_pos);
+        [
+            (local.x / self.cell_size.x).round() as i32,
+            (local.z / self.cell_size.y).round() as i32,
+        ]
+    }
+
+    /// Composite another terrain's heights into this one, vertex by vertex, for every chunk
+    /// both terrains have. `mode`: 0 = Max, 1 = Min, 2 = Add, 3 = Replace. Both terrains must
+    /// share `dimensions` and `cell_size`. Merged seam vertices are written to every loaded
+    /// chunk that stores them, neighbours included, and every changed chunk is re-meshed.
+    /// Not undoable. Returns the number of chunks changed.
+    #[func]
+    pub fn merge_heights_from(&mut self, other: Gd<PixyTerrain>, mode: i32) -> i32 {
+        if other.instance_id() == self.base().instance_id() {
+            godot_error!("PixyTerrain: cannot merge a terrain into itself");
+            return 0;
+        }
+        let other = other.bind();
+        if other.dimensions != self.dimensions || other.cell_size != self.cell_size {
+            godot_error!(
+                "PixyTerrain: merge_heights_from needs matching grids, got {:?}/{:?} vs {:?}/{:?}",
+                other.dimensions,
+                other.cell_size,
+                self.dimensions,
+                self.cell_size
+            );
+            return 0;
+        }
+        let mode = HeightMergeMode::from_index(mode);
+        let dim = self.dimensions;
+
+        let keys: Vec<[i32; 2]> = sorted_chunk_keys(&self.chunks)
+            .into_iter()
+            .filter(|key| other.chunks.contains_key(key))
+            .collect();
+        let merged = merged_vertex_heights(
+            &keys,
+            dim,
+            mode,
+            |key, [x, z]| self.chunks.get(&key)?.bind().get_height_at(x, z),
+            |key, [x, z]| other.chunks.get(&key)?.bind().get_height_at(x, z),
+        );
+        let changed = write_to_owners(&merged, dim, |key, [x, z], h| {
+            let Some(chunk) = self.chunks.get_mut(&key) else {
+                return false;
+            };
+            chunk.bind_mut().set_height_at(x, z, h);
+            true
+        });
+        for key in &changed {
+            if let Some(chunk) = self.chunks.get_mut(key) {
+                chunk.bind_mut().regenerate_mesh();
+            }
+        }
+        changed.len() as i32
+    }
+
+    /// Carve a riverbed along `curve` (terrain-local): vertices within `width / 2` of the
+    /// curve in XZ are lowered toward `curve_y - depth` with a U-shaped cross-section, so the
+    /// channel bottom follows the curve and the banks rise to the sides. Never raises terrain.
+    /// Applied as one composite pattern; returns the pattern that undoes it.
+    #[func]
+    pub fn carve_river(&mut self, curve: Gd<Curve3D>, width: f32, depth: f32) -> VarDictionary {
+        let points: Vec<Vector3> = curve.get_baked_points().as_slice().to_vec();
+        let half_width = width.abs() * 0.5;
+        let mut do_height = VarDictionary::new();
+        let mut undo_height = VarDictionary::new();
+        if points.is_empty() || half_width <= 0.0 {
+            return VarDictionary::new();
+        }
+
+        let dim = self.dimensions;
+        for key in sorted_chunk_keys(&self.chunks) {
+            let chunk = self.chunks[&key].bind();
+            let mut do_chunk = VarDictionary::new();
+            let mut undo_chunk = VarDictionary::new();
+            for z in 0..dim.z {
+                for x in 0..dim.x {
+                    let xz = vertex_position(key, [x, z], dim, self.cell_size);
+                    let Some((distance, curve_y)) = closest_on_path_xz(&points, xz) else {
+                        continue;
+                    };
+                    let Some(old) = chunk.get_height_at(x, z) else {
+                        continue;
+                    };
+                    let [gx, gz] = global_vertex(key, [x, z], dim);
+                    let carved = self.limit_height(
+                        Vector2i::new(gx, gz),
+                        river_height(old, curve_y - depth, distance, half_width),
+                    );
+                    if carved < old {
+                        do_chunk.set(Vector2i::new(x, z), carved);
+                        undo_chunk.set(Vector2i::new(x, z), old);
+                    }
+                }
+            }
+            if !do_chunk.is_empty() {
+                do_height.set(Vector2i::new(key[0], key[1]), do_chunk);
+                undo_height.set(Vector2i::new(key[0], key[1]), undo_chunk);
+            }
+        }
+
+        let mut do_patterns = VarDictionary::new();
+        let mut undo_patterns = VarDictionary::new();
+        if !do_height.is_empty() {
+            do_patterns.set("height", do_height);
+            undo_patterns.set("height", undo_height);
+            self.apply_composite_pattern(do_patterns);
+        }
+        undo_patterns
+    }
+
+    /// Coordinates of the chunk containing a world-space position.
+    #[func]
+    pub fn world_to_chunk(&self, world_pos: Vector3) -> Vector2i {
+        let local = self.base().to_local(world_pos);
+        let [x, z] = chunk_coords_at(
+            Vector2::new(local.x, local.z),
+            self.dimensions,
+            self.cell_size,
+        );
+        Vector2i::new(x, z)
+    }
+
+    /// Replace terrain heights with a script callback `func(world_x: float, world_z: float) -> float`.
+    /// The callback is baked into every existing chunk's heightmap immediately (discarding height
+    /// edits) and is also used for chunks added later, until `clear_custom_height_source()`.
+    #[func]
+    pub fn set_custom_height_source(&mut self, callable: Callable) {
+        if !callable.is_valid() {
+            godot_warn!("PixyTerrain: custom height source is not a valid Callable");
+            return;
+        }
+        for key in sorted_chunk_keys(&self.chunks) {
+            if let Some(chunk) = self.chunks.get(&key) {
+                let mut chunk = chunk.clone();
+                let mut bind = chunk.bind_mut();
+                bind.bake_heights(|x, z| sample_height_source(&callable, x, z));
+                bind.regenerate_mesh();
+            }
+        }
+        self.custom_height_source = Some(callable);
+    }
+
+    /// Go back to `noise_hmap` for new chunks. Existing heights are left as they are.
+    #[func]
+    pub fn clear_custom_height_source(&mut self) {
+        self.custom_height_source = None;
+    }
+
+    /// Get the merge threshold for the current merge mode.
+    #[func]
+    pub fn get_merge_threshold(&self) -> f32 {
+        MergeMode::from_index(self.merge_mode).threshold()
+    }
+
+    /// Create a new chunk at the given chunk coordinates, copying shared edges from neighbors.
+    #[func]
+    pub fn add_new_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        // Ensure material exists (may not yet if called before _deferred_enter_tree)
+        self.ensure_terrain_material();
+        self.ensure_grass_material();
+        self.force_batch_update();
+
+        let chunk_coords = Vector2i::new(chunk_x, chunk_z);
+        let mut new_chunk = Gd::<PixyTerrainChunk>::from_init_fn(PixyTerrainChunk::new_with_base);
+
+        new_chunk.set_name(&format!("Chunk ({}, {})", chunk_x, chunk_z));
+        {
+            let mut chunk_bind = new_chunk.bind_mut();
+            chunk_bind.chunk_coords = chunk_coords;
+            chunk_bind.merge_mode = self.merge_mode;
+        }
+
+        // Add to tree and initialize
+        self.add_chunk_internal(chunk_coords, new_chunk.clone(), false);
+
+        // Custom height source replaces the noise heights (edges below still win)
+        if let Some(ref source) = self.custom_height_source {
+            new_chunk
+                .bind_mut()
+                .bake_heights(|x, z| sample_height_source(source, x, z));
+        }
+
+        // Copy shared edges from adjacent chunks
+        let dim = self.dimensions;
+
+        // Left neighbor: copy rightmost column → new chunk leftmost column
+        if let Some(left) = self.chunks.get(&[chunk_x - 1, chunk_z]).cloned() {
+            let left_bind = left.bind();
+            let mut new_bind = new_chunk.bind_mut();
+            for z in 0..dim.z {
+                if let Some(h) = left_bind.get_height_at(dim.x - 1, z) {
+                    new_bind.set_height_at(0, z, h);
+                }
+            }
+        }
+
+        // Right neighbor
+        if let Some(right) = self.chunks.get(&[chunk_x + 1, chunk_z]).cloned() {
+            let right_bind = right.bind();
+            let mut new_bind = new_chunk.bind_mut();
+            for z in 0..dim.z {
+                if let Some(h) = right_bind.get_height_at(0, z) {
+                    new_bind.set_height_at(dim.x - 1, z, h);
+                }
+            }
+        }
+
+        // Up neighbor: copy bottom row → new chunk top row
+        if let Some(up) = self.chunks.get(&[chunk_x, chunk_z - 1]).cloned() {
+            let up_bind = up.bind();
+            let mut new_bind = new_chunk.bind_mut();
+            for x in 0..dim.x {
+                if let Some(h) = up_bind.get_height_at(x, dim.z - 1) {
+                    new_bind.set_height_at(x, 0, h);
+                }
+            }
+        }
+
+        // Down neighbor
+        if let Some(down) = self.chunks.get(&[chunk_x, chunk_z + 1]).cloned() {
+            let down_bind = down.bind();
+            let mut new_bind = new_chunk.bind_mut();
+            for x in 0..dim.x {
+                if let Some(h) = down_bind.get_height_at(x, 0) {
+                    new_bind.set_height_at(x, dim.z - 1, h);
+                }
+            }
+        }
+
+        // Generate mesh
+        new_chunk.bind_mut().regenerate_mesh();
+    }
+
+    fn add_chunk_internal(
+        &mut self,
+        coords: Vector2i,
+        mut chunk: Gd<PixyTerrainChunk>,
+        regenerate: bool,
+    ) {
+        let terrain_config = self.make_terrain_config();
+        let grass_config = self.make_grass_config();
+        let flower_config = self.make_flower_config();
+        let noise = self.noise_hmap.clone();
+        let material = self.chunk_material();
+
+        self.chunks.insert([coords.x, coords.y], chunk.clone());
+        self.lod_keys = None;
+
+        {
+            let mut chunk_bind = chunk.bind_mut();
+            chunk_bind.chunk_coords = coords;
+            chunk_bind.set_terrain_config(terrain_config);
+        }
+
+        self.base_mut().add_child(&chunk);
+        if self.hidden_chunks.contains(&[coords.x, coords.y]) {
+            chunk.set_visible(false);
+        }
+
+        // Position the chunk in world space
+        let dim = self.dimensions;
+        let cell = self.cell_size;
+        let pos = Vector3::new(
+            coords.x as f32 * ((dim.x - 1) as f32 * cell.x),
+            0.0,
+            coords.y as f32 * ((dim.z - 1) as f32 * cell.y),
+        );
+        chunk.set_position(pos);
+
+        // Set owner for editor persistence
+        if Engine::singleton().is_editor_hint() {
+            if let Some(mut editor) = Engine::singleton().get_singleton("EditorInterface") {
+                let scene_root = editor.call("get_edited_scene_root", &[]);
+                if let Ok(root) = scene_root.try_to::<Gd<Node>>() {
+                    Self::set_owner_recursive(&mut chunk.clone().upcast::<Node>(), &root);
+                }
+            }
+        }
+
+        chunk
+            .bind_mut()
+            .initialize_terrain(regenerate, noise, material, grass_config, flower_config);
+
+        godot_print!("PixyTerrain: Added chunk at ({}, {})", coords.x, coords.y);
     }
 
     fn set_owner_recursive(node: &mut Gd<Node>, owner: &Gd<Node>) {
@@ -1111,11 +2193,24 @@ impl PixyTerrain {
     /// Apply a composite pattern action. Called by undo/redo.
     /// `patterns` is a VarDictionary with keys: "height", "color_0", "color_1",
     /// "wall_color_0", "wall_color_1", "grass_mask".
-    /// Each value is Dict<Vector2i(chunk), Dict<Vector2i(cell), value>>.
+    /// Each value is Dict<Vector2i(chunk), Dict<Vector2i(cell), value>>, written verbatim.
+    /// "height_limit" is flat instead: Dict<Vector2i(terrain-wide vertex), Vector2(min, max)>,
+    /// where a nil value removes the vertex's limit.
     #[func]
     pub fn apply_composite_pattern(&mut self, patterns: VarDictionary) {
         let mut affected_chunks: HashMap<[i32; 2], Gd<PixyTerrainChunk>> = HashMap::new();
 
+        // Limits are keyed by terrain-wide vertex (nil clears one) and need no re-mesh
+        if let Some(limits) = patterns.get("height_limit") {
+            for (vertex, limit) in limits.to::<VarDictionary>().iter_shared() {
+                if limit.is_nil() {
+                    self.height_limits.remove(vertex);
+                } else {
+                    self.height_limits.set(vertex, limit);
+                }
+            }
+        }
+
         let keys_in_order = [
             "wall_color_0",
             "wall_color_1",
@@ -1183,12 +2278,254 @@ impl PixyTerrain {
             }
         }
 
-        // Regenerate mesh once per affected chunk
-        for (_, mut chunk) in affected_chunks {
-            chunk.bind_mut().regenerate_mesh();
+        // Regenerate mesh once per affected chunk; large edits are spread across frames
+        let budget = self.max_remesh_per_frame.max(0) as usize;
+        let keys = sorted_chunk_keys(&affected_chunks);
+        if self.debug_draw_affected_chunks {
+            self.draw_affected_outline(&keys);
+        }
+        if budget == 0 || keys.len() <= budget {
+            for key in keys {
+                if let Some(chunk) = affected_chunks.get_mut(&key) {
+                    chunk.bind_mut().regenerate_mesh();
+                }
+            }
+        } else {
+            enqueue_remesh(&mut self.remesh_queue, keys);
+        }
+    }
+
+    /// Outline the AABBs of `keys` with a line mesh that `fade_affected_outline` fades out.
+    fn draw_affected_outline(&mut self, keys: &[[i32; 2]]) {
+        let mut lines = ImmediateMesh::new_gd();
+        if !keys.is_empty() {
+            lines.surface_begin(PrimitiveType::LINES);
+            for key in keys {
+                let aabb = self.get_chunk_aabb(Vector2i::new(key[0], key[1]));
+                for [a, b] in aabb_edges(aabb) {
+                    lines.surface_add_vertex(a);
+                    lines.surface_add_vertex(b);
+                }
+            }
+            lines.surface_end();
+        }
+
+        if self.affected_outline.is_none() {
+            let mut material = StandardMaterial3D::new_gd();
+            material.set_shading_mode(ShadingMode::UNSHADED);
+            material.set_transparency(Transparency::ALPHA);
+            let mut outline = MeshInstance3D::new_alloc();
+            outline.set_name("AffectedChunksDebug");
+            outline.set_material_override(&material);
+            // No owner: the outline is never saved with the scene
+            self.base_mut().add_child(&outline);
+            self.affected_outline = Some(outline);
+            self.affected_outline_material = Some(material);
+        }
+        if let Some(outline) = self.affected_outline.as_mut() {
+            outline.set_mesh(&lines);
+            outline.set_visible(true);
+        }
+        self.affected_outline_time = AFFECTED_OUTLINE_FADE_SECS;
+        self.fade_affected_outline(0.0);
+    }
+
+    fn fade_affected_outline(&mut self, delta: f32) {
+        if self.affected_outline_time <= 0.0 {
+            return;
+        }
+        self.affected_outline_time = (self.affected_outline_time - delta).max(0.0);
+        let alpha = self.affected_outline_time / AFFECTED_OUTLINE_FADE_SECS;
+        if let Some(material) = self.affected_outline_material.as_mut() {
+            material.set_albedo(Color::from_rgba(1.0, 0.8, 0.0, alpha));
+        }
+        if alpha <= 0.0 {
+            if let Some(outline) = self.affected_outline.as_mut() {
+                outline.set_visible(false);
+            }
+        }
+    }
+
+    /// Re-mesh up to `max_remesh_per_frame` queued chunks.
+    fn drain_remesh_queue(&mut self) {
+        if self.remesh_queue.is_empty() {
+            return;
+        }
+        let budget = self.max_remesh_per_frame.max(0) as usize;
+        for key in take_remesh_batch(&mut self.remesh_queue, budget) {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                chunk.bind_mut().regenerate_mesh();
+            }
+        }
+    }
+
+    /// Pick each chunk's mesh LOD from the active camera's distance to its grid center,
+    /// re-meshing at most `max_remesh_per_frame` chunks whose level changed. Clearing
+    /// `lod_distances` brings every chunk back to full resolution.
+    /// A pass only runs when the camera moved more than a cell, the thresholds or chunks
+    /// changed, or the previous pass ran out of budget; with LOD off and every chunk at
+    /// full resolution it returns straight away.
+    fn update_chunk_lods(&mut self) {
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+        if self.lod_distances.is_empty() && !self.lod_active {
+            return;
+        }
+        let camera_pos = self
+            .base()
+            .get_viewport()
+            .and_then(|vp| vp.get_camera_3d())
+            .map(|camera| self.base().to_local(camera.get_global_position()));
+        // Without a camera every chunk falls back to full resolution
+        let thresholds: &[f32] = match camera_pos {
+            Some(_) => self.lod_distances.as_slice(),
+            None => &[],
+        };
+        let camera_pos = camera_pos.unwrap_or(Vector3::ZERO);
+        let min_move = self.cell_size.x.min(self.cell_size.y);
+        if !self.lod_pending
+            && self.lod_keys.is_some()
+            && self.lod_thresholds == thresholds
+            && !camera_moved(self.lod_camera, camera_pos, min_move)
+        {
+            return;
+        }
+        if self.lod_thresholds != thresholds {
+            self.lod_thresholds = thresholds.to_vec();
+        }
+        self.lod_camera = Some(camera_pos);
+
+        let budget = self.max_remesh_per_frame.max(0) as usize;
+        let height = self.dimensions.y as f32;
+        let keys = self
+            .lod_keys
+            .get_or_insert_with(|| sorted_chunk_keys(&self.chunks));
+        let mut remeshed = 0;
+        let mut active = false;
+        let mut pending = false;
+        for key in keys.iter() {
+            let Some(chunk) = self.chunks.get_mut(key) else {
+                continue;
+            };
+            let coords = Vector2i::new(key[0], key[1]);
+            let center = chunk_aabb(coords, self.dimensions, self.cell_size, 0.0, height).center();
+            let level = lod_for_distance(camera_pos.distance_to(center), &self.lod_thresholds);
+            let mut lod = chunk.bind().lod();
+            if lod != level {
+                if budget > 0 && remeshed >= budget {
+                    pending = true;
+                } else {
+                    chunk.bind_mut().set_lod(level);
+                    remeshed += 1;
+                    lod = level;
+                }
+            }
+            active |= lod > 0;
+        }
+        self.lod_active = active;
+        self.lod_pending = pending;
+    }
+
+    /// Contour where the terrain surface crosses height `plane_y`, in terrain-local XZ.
+    /// Points `2i` and `2i + 1` form one segment, matching `CanvasItem.draw_multiline`.
+    #[func]
+    pub fn get_cross_section_outline(&self, plane_y: f32) -> PackedVector2Array {
+        let mut outline = PackedVector2Array::new();
+        for key in sorted_chunk_keys(&self.chunks) {
+            let chunk = self.chunks[&key].bind();
+            let origin = vertex_position(key, [0, 0], self.dimensions, self.cell_size);
+            for [a, b] in contour_segments(&chunk.height_map, plane_y, self.cell_size) {
+                outline.push(origin + a);
+                outline.push(origin + b);
+            }
+        }
+        outline
+    }
+
+    /// Grayscale top-down preview of the heights `noise_hmap` would generate (amplitude and
+    /// terracing applied) over the loaded chunks' footprint, without touching any chunk.
+    /// Black is `-dimensions.y`, white `+dimensions.y`. Returns null without a noise resource.
+    #[func]
+    pub fn preview_noise_heights(&self, resolution: i32) -> Option<Gd<Image>> {
+        let noise = self.noise_hmap.clone()?;
+        let size = resolution.clamp(8, 1024);
+        let dim = self.dimensions;
+        let keys = sorted_chunk_keys(&self.chunks);
+        let (min_x, max_x) = (
+            keys.iter().map(|k| k[0]).min().unwrap_or(0),
+            keys.iter().map(|k| k[0]).max().unwrap_or(0),
+        );
+        let (min_z, max_z) = (
+            keys.iter().map(|k| k[1]).min().unwrap_or(0),
+            keys.iter().map(|k| k[1]).max().unwrap_or(0),
+        );
+        // Noise is sampled in terrain-wide vertex coords, like generate_height_map_with_noise
+        let origin = Vector2::new((min_x * (dim.x - 1)) as f32, (min_z * (dim.z - 1)) as f32);
+        let span = Vector2::new(
+            ((max_x - min_x + 1) * (dim.x - 1)) as f32,
+            ((max_z - min_z + 1) * (dim.z - 1)) as f32,
+        );
+
+        let config = self.make_terrain_config();
+        let mut image = Image::create(size, size, false, godot::classes::image::Format::L8)?;
+        let step = (size - 1).max(1) as f32;
+        for py in 0..size {
+            for px in 0..size {
+                let x = origin.x + span.x * px as f32 / step;
+                let z = origin.y + span.y * py as f32 / step;
+                let height = noise_height(noise.get_noise_2d(x, z), dim.y, &config);
+                let gray = preview_gray(height, dim.y);
+                image.set_pixel(px, py, Color::from_rgb(gray, gray, gray));
+            }
+        }
+        Some(image)
+    }
+
+    /// Set `noise_amplitude`. Without `discard_edits` only heights generated later use it.
+    /// With `discard_edits`, every chunk's heights are regenerated from `noise_hmap`, which
+    /// throws away sculpted heights and cannot be undone. Calls within one frame coalesce
+    /// into a single rebuild, so a Tween can drive `set_noise_amplitude.bind(true)`.
+    /// Nothing is regenerated without a `noise_hmap` or while a custom height source is set.
+    #[func]
+    pub fn set_noise_amplitude(&mut self, amplitude: f32, discard_edits: bool) {
+        self.noise_amplitude = amplitude.max(0.0);
+        if !discard_edits {
+            return;
+        }
+        if self.noise_hmap.is_none() {
+            godot_warn!("PixyTerrain: set_noise_amplitude: no noise_hmap, heights kept");
+            return;
+        }
+        self.noise_rebake_pending = true;
+    }
+
+    fn rebake_noise_heights(&mut self) {
+        if self.custom_height_source.is_some() {
+            return;
+        }
+        // Regenerating without noise would flatten every chunk to 0
+        let Some(noise) = self.noise_hmap.clone() else {
+            return;
+        };
+        let terrain_config = self.make_terrain_config();
+        self.remesh_queue.clear();
+        for key in sorted_chunk_keys(&self.chunks) {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                let mut bind = chunk.bind_mut();
+                bind.set_terrain_config(terrain_config.clone());
+                bind.generate_height_map_with_noise(Some(noise.clone()));
+                bind.regenerate_mesh();
+            }
         }
     }
 
+    /// Number of chunks still waiting to be re-meshed after a large edit.
+    #[func]
+    pub fn get_pending_remesh_count(&self) -> i32 {
+        self.remesh_queue.len() as i32
+    }
+
     #[func]
     pub fn save_to_preset(&mut self) {
         if self.current_texture_preset.is_none() {
@@ -1264,7 +2601,7 @@ impl PixyTerrain {
     /// Regenerate grass on all chunks.
     #[func]
     pub fn regenerate_all_grass(&mut self) {
-        let chunk_keys: Vec<[i32; 2]> = self.chunks.keys().cloned().collect();
+        let chunk_keys: Vec<[i32; 2]> = sorted_chunk_keys(&self.chunks);
         for key in chunk_keys {
             if let Some(chunk) = self.chunks.get(&key) {
                 let mut chunk = chunk.clone();
@@ -1274,6 +2611,416 @@ impl PixyTerrain {
     }
 }
 
+/// Call a custom height source, treating non-numeric results as 0.0.
+fn sample_height_source(source: &Callable, world_x: f32, world_z: f32) -> f32 {
+    source
+        .call(&[world_x.to_variant(), world_z.to_variant()])
+        .try_to::<f32>()
+        .unwrap_or(0.0)
+}
+
+/// Height of terrain-wide vertex `(gx, gz)` from the first loaded chunk that stores it.
+/// Vertices on the terrain's +X/+Z edge are only stored by the chunk before them.
+fn owned_vertex_height(
+    gx: i32,
+    gz: i32,
+    dim: Vector3i,
+    height_at: impl Fn([i32; 2], [i32; 2]) -> Option<f32>,
+) -> Option<f32> {
+    vertex_owners(gx, gz, dim)
+        .into_iter()
+        .find_map(|(key, cell)| height_at(key, cell))
+}
+
+/// Upward normal of a heightmap vertex from its neighbors' heights `[-x, +x, -z, +z]`.
+/// Central differences where both neighbors exist, one-sided at the terrain edge.
+fn heightmap_normal(center: f32, neighbors: [Option<f32>; 4], cell_size: Vector2) -> Vector3 {
+    let slope = |lo: Option<f32>, hi: Option<f32>, spacing: f32| match (lo, hi) {
+        (Some(l), Some(h)) => (h - l) / (2.0 * spacing),
+        (Some(l), None) => (center - l) / spacing,
+        (None, Some(h)) => (h - center) / spacing,
+        (None, None) => 0.0,
+    };
+    let dx = slope(neighbors[0], neighbors[1], cell_size.x);
+    let dz = slope(neighbors[2], neighbors[3], cell_size.y);
+    Vector3::new(-dx, 1.0, -dz).normalized()
+}
+
+/// Centers of flat areas in a terrain-wide vertex height map (see `find_flat_areas`),
+/// sorted so results don't depend on HashMap order.
+fn flat_area_centers(
+    heights: &HashMap<[i32; 2], f32>,
+    cell_size: Vector2,
+    min_radius: f32,
+    max_slope_degrees: f32,
+) -> Vec<[i32; 2]> {
+    let min_normal_y = max_slope_degrees.to_radians().cos();
+    let flat: HashSet<[i32; 2]> = heights
+        .iter()
+        .filter(|&(&[x, z], &h)| {
+            let neighbors = [[-1, 0], [1, 0], [0, -1], [0, 1]]
+                .map(|[dx, dz]| heights.get(&[x + dx, z + dz]).copied());
+            heightmap_normal(h, neighbors, cell_size).y >= min_normal_y
+        })
+        .map(|(&v, _)| v)
+        .collect();
+
+    // Vertices whose whole disc of `min_radius` is flat (and loaded)
+    let reach_x = (min_radius / cell_size.x).floor() as i32;
+    let reach_z = (min_radius / cell_size.y).floor() as i32;
+    let disc: Vec<[i32; 2]> = (-reach_z..=reach_z)
+        .flat_map(|dz| (-reach_x..=reach_x).map(move |dx| [dx, dz]))
+        .filter(|&[dx, dz]| {
+            let offset = Vector2::new(dx as f32 * cell_size.x, dz as f32 * cell_size.y);
+            offset.length() <= min_radius
+        })
+        .collect();
+    let candidates: HashSet<[i32; 2]> = flat
+        .iter()
+        .filter(|v| {
+            disc.iter()
+                .all(|d| flat.contains(&[v[0] + d[0], v[1] + d[1]]))
+        })
+        .copied()
+        .collect();
+
+    let mut seeds: Vec<[i32; 2]> = candidates.iter().copied().collect();
+    seeds.sort_unstable();
+    let mut visited = HashSet::new();
+    let mut centers = Vec::new();
+    for seed in seeds {
+        if !visited.insert(seed) {
+            continue;
+        }
+        let mut area = vec![seed];
+        let mut next = 0;
+        while next < area.len() {
+            let v = area[next];
+            next += 1;
+            for d in CELL_NEIGHBORS {
+                let n = [v[0] + d[0], v[1] + d[1]];
+                if candidates.contains(&n) && visited.insert(n) {
+                    area.push(n);
+                }
+            }
+        }
+        let count = area.len() as f32;
+        let cx = area.iter().map(|v| v[0] as f32).sum::<f32>() / count;
+        let cz = area.iter().map(|v| v[1] as f32).sum::<f32>() / count;
+        let dist = |v: &[i32; 2]| (v[0] as f32 - cx).powi(2) + (v[1] as f32 - cz).powi(2);
+        if let Some(center) = area.iter().min_by(|a, b| dist(a).total_cmp(&dist(b))) {
+            centers.push(*center);
+        }
+    }
+    centers
+}
+
+/// The 12 edges of an AABB as line segments.
+fn aabb_edges(aabb: Aabb) -> Vec<[Vector3; 2]> {
+    // Corner bits: 1 = +x, 2 = +y, 4 = +z; each edge flips one bit of a corner where it's unset
+    let corner = |bits: usize| {
+        let pick = |bit: usize| if bits & bit != 0 { 1.0 } else { 0.0 };
+        aabb.position + aabb.size * Vector3::new(pick(1), pick(2), pick(4))
+    };
+    (0..8)
+        .flat_map(|c| [1, 2, 4].into_iter().map(move |bit| (c, bit)))
+        .filter(|(c, bit)| c & bit == 0)
+        .map(|(c, bit)| [corner(c), corner(c | bit)])
+        .collect()
+}
+
+const CELL_NEIGHBORS: [[i32; 2]; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
+
+/// Add every cell within Manhattan distance `n` of the set.
+fn grow_cells(cells: &HashSet<[i32; 2]>, n: i32) -> HashSet<[i32; 2]> {
+    let mut grown = cells.clone();
+    for _ in 0..n {
+        let ring: Vec<[i32; 2]> = grown
+            .iter()
+            .flat_map(|c| {
+                CELL_NEIGHBORS
+                    .iter()
+                    .map(move |d| [c[0] + d[0], c[1] + d[1]])
+            })
+            .collect();
+        grown.extend(ring);
+    }
+    grown
+}
+
+/// Keep only cells whose whole Manhattan neighborhood of radius `n` is in the set.
+fn shrink_cells(cells: &HashSet<[i32; 2]>, n: i32) -> HashSet<[i32; 2]> {
+    let mut shrunk = cells.clone();
+    for _ in 0..n {
+        shrunk = shrunk
+            .iter()
+            .filter(|c| {
+                CELL_NEIGHBORS
+                    .iter()
+                    .all(|d| shrunk.contains(&[c[0] + d[0], c[1] + d[1]]))
+            })
+            .copied()
+            .collect();
+    }
+    shrunk
+}
+
+/// Append chunk keys to the re-mesh queue, skipping ones already waiting.
+fn enqueue_remesh(queue: &mut VecDeque<[i32; 2]>, keys: impl IntoIterator<Item = [i32; 2]>) {
+    for key in keys {
+        if !queue.contains(&key) {
+            queue.push_back(key);
+        }
+    }
+}
+
+/// Pop the next `budget` keys from the re-mesh queue (all of them when `budget` is 0).
+fn take_remesh_batch(queue: &mut VecDeque<[i32; 2]>, budget: usize) -> Vec<[i32; 2]> {
+    let count = if budget == 0 {
+        queue.len()
+    } else {
+        budget.min(queue.len())
+    };
+    queue.drain(..count).collect()
+}
+
+/// How `merge_heights_from` combines this terrain's height with the other terrain's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeightMergeMode {
+    Max,
+    Min,
+    Add,
+    Replace,
+}
+
+impl HeightMergeMode {
+    fn from_index(idx: i32) -> Self {
+        match idx {
+            1 => HeightMergeMode::Min,
+            2 => HeightMergeMode::Add,
+            3 => HeightMergeMode::Replace,
+            _ => HeightMergeMode::Max,
+        }
+    }
+
+    fn merge(self, ours: f32, theirs: f32) -> f32 {
+        match self {
+            HeightMergeMode::Max => ours.max(theirs),
+            HeightMergeMode::Min => ours.min(theirs),
+            HeightMergeMode::Add => ours + theirs,
+            HeightMergeMode::Replace => theirs,
+        }
+    }
+}
+
+/// Heights from merging `theirs` into `ours` over the vertices of chunks `keys`, as
+/// terrain-wide vertex -> merged height, for vertices whose height changes. Seam vertices
+/// are merged once, however many of the chunks share them.
+fn merged_vertex_heights(
+    keys: &[[i32; 2]],
+    dim: Vector3i,
+    mode: HeightMergeMode,
+    ours: impl Fn([i32; 2], [i32; 2]) -> Option<f32>,
+    theirs: impl Fn([i32; 2], [i32; 2]) -> Option<f32>,
+) -> BTreeMap<[i32; 2], f32> {
+    let vertices: BTreeSet<[i32; 2]> = keys
+        .iter()
+        .flat_map(|&key| chunk_vertices(key, dim))
+        .collect();
+    vertices
+        .into_iter()
+        .filter_map(|[gx, gz]| {
+            let ours = owned_vertex_height(gx, gz, dim, &ours)?;
+            let theirs = owned_vertex_height(gx, gz, dim, &theirs)?;
+            let merged = mode.merge(ours, theirs);
+            (merged != ours).then_some(([gx, gz], merged))
+        })
+        .collect()
+}
+
+/// Write terrain-wide vertex heights into every chunk that stores each vertex. `set` writes
+/// one `(chunk key, local cell)` and returns false when that chunk isn't loaded. Returns the
+/// chunks written, which all need a re-mesh.
+fn write_to_owners(
+    heights: &BTreeMap<[i32; 2], f32>,
+    dim: Vector3i,
+    mut set: impl FnMut([i32; 2], [i32; 2], f32) -> bool,
+) -> BTreeSet<[i32; 2]> {
+    let mut written = BTreeSet::new();
+    for (&[gx, gz], &h) in heights {
+        for (key, cell) in vertex_owners(gx, gz, dim) {
+            if set(key, cell, h) {
+                written.insert(key);
+            }
+        }
+    }
+    written
+}
+
+/// XZ distance from `p` to the polyline `points`, and the polyline's Y at the closest point.
+fn closest_on_path_xz(points: &[Vector3], p: Vector2) -> Option<(f32, f32)> {
+    let xz = |v: Vector3| Vector2::new(v.x, v.z);
+    if let [only] = points {
+        return Some((xz(*only).distance_to(p), only.y));
+    }
+    points
+        .windows(2)
+        .map(|seg| {
+            let (a, b) = (xz(seg[0]), xz(seg[1]));
+            let ab = b - a;
+            let len_sq = ab.length_squared();
+            let t = if len_sq > 0.0 {
+                ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let y = seg[0].y + (seg[1].y - seg[0].y) * t;
+            ((a + ab * t).distance_to(p), y)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// River cross-section: `bed` at the center line, easing back to `height` at `half_width`
+/// (parabolic U). Only ever lowers.
+fn river_height(height: f32, bed: f32, distance: f32, half_width: f32) -> f32 {
+    if distance >= half_width {
+        return height;
+    }
+    let u = distance / half_width;
+    let carve = 1.0 - u * u;
+    height.min(height + (bed - height) * carve)
+}
+
+/// Rewrite each `(c0[i], c1[i])` pair whose texture slot `table` maps elsewhere.
+/// Returns whether any pair changed.
+fn remap_color_pairs(c0: &mut [Color], c1: &mut [Color], table: &[u8; 16]) -> bool {
+    let mut changed = false;
+    for (a, b) in c0.iter_mut().zip(c1.iter_mut()) {
+        let from = TextureIndex::from_color_pair(*a, *b);
+        let to = TextureIndex(table[from.0 as usize]);
+        if to != from {
+            (*a, *b) = to.to_color_pair();
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Whether the camera moved at least `min_distance` from where the last LOD pass saw it
+/// (always true before the first pass).
+fn camera_moved(last: Option<Vector3>, now: Vector3, min_distance: f32) -> bool {
+    match last {
+        Some(last) => last.distance_to(now) >= min_distance,
+        None => true,
+    }
+}
+
+/// Mesh LOD for a chunk `distance` away: the number of `thresholds` it has reached.
+fn lod_for_distance(distance: f32, thresholds: &[f32]) -> u32 {
+    thresholds.iter().filter(|&&t| distance >= t).count() as u32
+}
+
+/// Keys of the chunks whose bounds lie entirely above height `y`.
+fn chunks_above(bounds: &[([i32; 2], Aabb)], y: f32) -> Vec<[i32; 2]> {
+    bounds
+        .iter()
+        .filter(|(_, aabb)| aabb.position.y > y)
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Sample positions from `min` to `max` inclusive, at most `step` apart.
+fn proxy_axis(min: f32, max: f32, step: f32) -> Vec<f32> {
+    let count = ((max - min) / step).ceil().max(1.0) as usize;
+    (0..=count)
+        .map(|i| min + (max - min) * i as f32 / count as f32)
+        .collect()
+}
+
+/// Proxy sample height at terrain-local `(x, z)`: the nearest heightmap vertex, looked up
+/// through its owning chunks so the terrain's +X/+Z edge has heights too.
+fn proxy_height(
+    x: f32,
+    z: f32,
+    dim: Vector3i,
+    cell_size: Vector2,
+    height_at: impl Fn([i32; 2], [i32; 2]) -> Option<f32>,
+) -> Option<f32> {
+    let gx = (x / cell_size.x).round() as i32;
+    let gz = (z / cell_size.y).round() as i32;
+    owned_vertex_height(gx, gz, dim, height_at)
+}
+
+/// Two triangles per grid quad (`xs` x `zs`, upward-facing winding), skipping quads with a
+/// corner where `height` has no value.
+fn grid_triangles(
+    xs: &[f32],
+    zs: &[f32],
+    height: impl Fn(f32, f32) -> Option<f32>,
+) -> Vec<Vector3> {
+    let heights: Vec<Vec<Option<f32>>> = zs
+        .iter()
+        .map(|&z| xs.iter().map(|&x| height(x, z)).collect())
+        .collect();
+    let point = |i: usize, j: usize| heights[j][i].map(|y| Vector3::new(xs[i], y, zs[j]));
+
+    let mut faces = Vec::new();
+    for j in 0..zs.len().saturating_sub(1) {
+        for i in 0..xs.len().saturating_sub(1) {
+            let corners = (
+                point(i, j),
+                point(i + 1, j),
+                point(i, j + 1),
+                point(i + 1, j + 1),
+            );
+            let (Some(a), Some(b), Some(c), Some(d)) = corners else {
+                continue;
+            };
+            faces.extend([a, b, c, b, d, c]);
+        }
+    }
+    faces
+}
+
+/// Preview brightness for a noise height: `-dim_y..dim_y` mapped onto `0..1`.
+fn preview_gray(height: f32, dim_y: i32) -> f32 {
+    (height / dim_y.max(1) as f32 * 0.5 + 0.5).clamp(0.0, 1.0)
+}
+
+/// Clamp `height` into `limit` = (min, max).
+fn clamp_to_limit(height: f32, limit: Vector2) -> f32 {
+    height.max(limit.x).min(limit.y)
+}
+
+/// Typed value of `key` in a dictionary read from a file; None if missing or the wrong type.
+fn dict_field<T: FromGodot>(dict: &VarDictionary, key: &str) -> Option<T> {
+    dict.get(key)?.try_to::<T>().ok()
+}
+
+/// Chunk keys sorted by (x, z) so iteration order doesn't depend on HashMap hashing.
+fn sorted_chunk_keys<V>(chunks: &HashMap<[i32; 2], V>) -> Vec<[i32; 2]> {
+    let mut keys: Vec<[i32; 2]> = chunks.keys().copied().collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// `bake_to_scene` children: one `(chunk key, node name, position)` per chunk, in key order.
+/// Positions match the chunk placement in `add_chunk_internal`.
+fn bake_layout<V>(
+    chunks: &HashMap<[i32; 2], V>,
+    dim: Vector3i,
+    cell_size: Vector2,
+) -> Vec<([i32; 2], String, Vector3)> {
+    sorted_chunk_keys(chunks)
+        .into_iter()
+        .map(|key| {
+            let origin = vertex_position(key, [0, 0], dim, cell_size);
+            let name = format!("Chunk ({}, {})", key[0], key[1]);
+            (key, name, Vector3::new(origin.x, 0.0, origin.y))
+        })
+        .collect()
+}
+
 /// Extract a `Gd<Texture2D>` from a VarArray slot, returning None for nil/out-of-bounds.
 pub fn get_variant_texture(arr: &VarArray, i: usize) -> Option<Gd<Texture2D>> {
     if i >= arr.len() {
@@ -1308,3 +3055,402 @@ fn load_default_texture(path: &str) -> Option<Gd<Texture2D>> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_chunk_keys_ignores_insertion_order() {
+        let coords = [[1, 0], [-1, 2], [0, 0], [0, -1], [1, -3]];
+        let forward: HashMap<[i32; 2], ()> = coords.iter().map(|c| (*c, ())).collect();
+        let reverse: HashMap<[i32; 2], ()> = coords.iter().rev().map(|c| (*c, ())).collect();
+
+        let expected = vec![[-1, 2], [0, -1], [0, 0], [1, -3], [1, 0]];
+        assert_eq!(sorted_chunk_keys(&forward), expected);
+        assert_eq!(sorted_chunk_keys(&reverse), expected);
+    }
+
+    #[test]
+    fn test_large_edit_remeshes_over_several_ticks() {
+        let mut queue = VecDeque::new();
+        let keys: Vec<[i32; 2]> = (0..10).map(|x| [x, 0]).collect();
+        enqueue_remesh(&mut queue, keys.clone());
+        // Re-editing a chunk that is still pending must not queue it twice
+        enqueue_remesh(&mut queue, [[3, 0], [9, 0]]);
+        assert_eq!(queue.len(), 10);
+
+        let mut ticks = Vec::new();
+        while !queue.is_empty() {
+            ticks.push(take_remesh_batch(&mut queue, 4));
+        }
+        assert_eq!(ticks.len(), 3);
+        assert_eq!(
+            ticks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+        assert_eq!(ticks.concat(), keys);
+    }
+
+    #[test]
+    fn test_zero_budget_drains_everything() {
+        let mut queue = VecDeque::new();
+        enqueue_remesh(&mut queue, (0..5).map(|z| [0, z]));
+        assert_eq!(take_remesh_batch(&mut queue, 0).len(), 5);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_grow_single_cell_is_plus_shape() {
+        let single: HashSet<[i32; 2]> = [[4, 7]].into_iter().collect();
+        let grown = grow_cells(&single, 1);
+        let expected: HashSet<[i32; 2]> = [[4, 7], [5, 7], [3, 7], [4, 8], [4, 6]]
+            .into_iter()
+            .collect();
+        assert_eq!(grown, expected);
+        // Radius 2 diamond: 1 + 4 + 8 cells
+        assert_eq!(grow_cells(&single, 2).len(), 13);
+    }
+
+    #[test]
+    fn test_shrink_undoes_grow() {
+        let single: HashSet<[i32; 2]> = [[0, 0]].into_iter().collect();
+        assert_eq!(shrink_cells(&grow_cells(&single, 3), 3), single);
+        assert!(shrink_cells(&single, 1).is_empty());
+    }
+
+    #[test]
+    fn test_invert_twice_is_identity() {
+        let dim = Vector3i::new(5, 4, 5);
+        let all: HashSet<[i32; 2]> = [[0, 0], [1, 0]]
+            .into_iter()
+            .flat_map(|key| chunk_vertices(key, dim))
+            .collect();
+        // Two chunks share their seam column: 5 * 5 + 4 * 5
+        assert_eq!(all.len(), 45);
+
+        let selection: HashSet<[i32; 2]> = [[0, 0], [4, 2], [8, 4]].into_iter().collect();
+        let inverted: HashSet<[i32; 2]> = all.difference(&selection).copied().collect();
+        assert_eq!(inverted.len(), 42);
+        let restored: HashSet<[i32; 2]> = all.difference(&inverted).copied().collect();
+        assert_eq!(restored, selection);
+    }
+
+    #[test]
+    fn test_far_edge_vertices_resolve_from_the_previous_chunk() {
+        // Only chunk (0, 0) is loaded, a ramp rising 1 unit per vertex along +X
+        let dim = Vector3i::new(33, 32, 33);
+        let height_at = |key: [i32; 2], [x, _z]: [i32; 2]| (key == [0, 0]).then_some(x as f32);
+
+        // The +X/+Z edge belongs to chunk (1, _) by div_euclid, which isn't loaded
+        assert_eq!(owned_vertex_height(32, 10, dim, height_at), Some(32.0));
+        assert_eq!(owned_vertex_height(32, 32, dim, height_at), Some(32.0));
+        assert_eq!(owned_vertex_height(5, 32, dim, height_at), Some(5.0));
+        assert_eq!(owned_vertex_height(33, 10, dim, height_at), None);
+
+        // So the edge normal uses the real ramp, not the flat fallback
+        let neighbors = [31, 33, 9, 11].map(|g| {
+            if g == 9 || g == 11 {
+                owned_vertex_height(32, g, dim, height_at)
+            } else {
+                owned_vertex_height(g, 10, dim, height_at)
+            }
+        });
+        let normal = heightmap_normal(32.0, neighbors, Vector2::new(1.0, 1.0));
+        assert!(normal.x < -0.5, "{normal:?}");
+    }
+
+    #[test]
+    fn test_heightmap_normal_flat_and_slope() {
+        let cell = Vector2::new(2.0, 2.0);
+        let flat = heightmap_normal(3.0, [Some(3.0); 4], cell);
+        assert!((flat - Vector3::UP).length() < 1e-6);
+
+        // 45 degree ramp rising along +X: height grows by one cell width per vertex
+        let ramp = heightmap_normal(4.0, [Some(2.0), Some(6.0), Some(4.0), Some(4.0)], cell);
+        let expected = Vector3::new(-1.0, 1.0, 0.0).normalized();
+        assert!((ramp - expected).length() < 1e-6, "{ramp:?}");
+
+        // Same ramp at the terrain edge (no +X neighbor) uses a one-sided difference
+        let edge = heightmap_normal(4.0, [Some(2.0), None, None, Some(4.0)], cell);
+        assert!((edge - expected).length() < 1e-6, "{edge:?}");
+    }
+
+    #[test]
+    fn test_flat_area_centers_finds_plateau() {
+        // 7x7 plateau at height 5 in the middle of a steep pyramid (3 units per vertex)
+        let mut heights = HashMap::new();
+        for z in 0..21 {
+            for x in 0..21 {
+                let ring = (x - 10i32).abs().max((z - 10i32).abs());
+                let h = 5.0 + 3.0 * (ring - 3).max(0) as f32;
+                heights.insert([x, z], h);
+            }
+        }
+        let cell = Vector2::new(1.0, 1.0);
+
+        assert_eq!(flat_area_centers(&heights, cell, 2.0, 10.0), vec![[10, 10]]);
+        // Plateau too small for the radius, and the slopes never qualify
+        assert!(flat_area_centers(&heights, cell, 4.0, 10.0).is_empty());
+        // A loose enough threshold accepts the slopes too
+        assert!(!flat_area_centers(&heights, cell, 2.0, 89.0).is_empty());
+    }
+
+    #[test]
+    fn test_aabb_edges_trace_the_box() {
+        let aabb = Aabb::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(4.0, 5.0, 6.0));
+        let edges = aabb_edges(aabb);
+        assert_eq!(edges.len(), 12);
+        // Four edges along each axis, each spanning the full size on that axis only
+        for axis in [
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 5.0, 0.0),
+            Vector3::new(0.0, 0.0, 6.0),
+        ] {
+            let along = edges.iter().filter(|[a, b]| *b - *a == axis).count();
+            assert_eq!(along, 4, "{axis:?}");
+        }
+    }
+
+    #[test]
+    fn test_clamp_to_limit() {
+        let bank = Vector2::new(f32::NEG_INFINITY, 4.0);
+        // A limited vertex refuses to rise above its max; the open side stays free
+        assert_eq!(clamp_to_limit(9.0, bank), 4.0);
+        assert_eq!(clamp_to_limit(-3.0, bank), -3.0);
+        let band = Vector2::new(1.0, 2.0);
+        assert_eq!(clamp_to_limit(0.0, band), 1.0);
+        assert_eq!(clamp_to_limit(1.5, band), 1.5);
+    }
+
+    #[test]
+    fn test_height_merge_modes() {
+        // Flat base at 2 with a hill layer peaking at 7 (0 away from the hill)
+        let base = [2.0, 2.0, 2.0];
+        let hill = [0.0, 7.0, 0.0];
+        let merged = |mode: i32| {
+            let mode = HeightMergeMode::from_index(mode);
+            [0, 1, 2].map(|i| mode.merge(base[i], hill[i]))
+        };
+        assert_eq!(merged(0), [2.0, 7.0, 2.0]);
+        assert_eq!(merged(1), [0.0, 2.0, 0.0]);
+        assert_eq!(merged(2), [2.0, 9.0, 2.0]);
+        assert_eq!(merged(3), hill);
+        assert_eq!(HeightMergeMode::from_index(99), HeightMergeMode::Max);
+    }
+
+    #[test]
+    fn test_merge_keeps_seams_consistent_with_unmerged_neighbours() {
+        // Ours: flat chunks (0, 0) and (1, 0). Theirs: only (0, 0), with a hill reaching
+        // its +X edge, which (1, 0) also stores as its x = 0 column
+        let dim = Vector3i::new(5, 8, 5);
+        let flat = vec![vec![1.0f32; 5]; 5];
+        let mut ours: HashMap<[i32; 2], Vec<Vec<f32>>> =
+            [([0, 0], flat.clone()), ([1, 0], flat.clone())].into();
+        let mut hill = vec![vec![0.0f32; 5]; 5];
+        for row in hill.iter_mut() {
+            row[3] = 3.0;
+            row[4] = 6.0;
+        }
+        let theirs: HashMap<[i32; 2], Vec<Vec<f32>>> = [([0, 0], hill)].into();
+        let lookup = |maps: &HashMap<[i32; 2], Vec<Vec<f32>>>, key: [i32; 2], [x, z]: [i32; 2]| {
+            maps.get(&key)
+                .and_then(|m| m.get(z as usize))
+                .and_then(|row| row.get(x as usize))
+                .copied()
+        };
+
+        let merged = merged_vertex_heights(
+            &[[0, 0]],
+            dim,
+            HeightMergeMode::Max,
+            |k, c| lookup(&ours, k, c),
+            |k, c| lookup(&theirs, k, c),
+        );
+        // Columns x = 3 and the x = 4 seam, each 5 vertices deep, merged once
+        assert_eq!(merged.len(), 10);
+        let changed = write_to_owners(&merged, dim, |key, [x, z], h| {
+            let Some(map) = ours.get_mut(&key) else {
+                return false;
+            };
+            map[z as usize][x as usize] = h;
+            true
+        });
+
+        // The hill lands in (0, 0), and (1, 0) is re-meshed with the same seam heights
+        assert_eq!(changed, BTreeSet::from([[0, 0], [1, 0]]));
+        for z in 0..5 {
+            assert_eq!(ours[&[0, 0]][z][3], 3.0);
+            assert_eq!(ours[&[0, 0]][z][4], 6.0);
+            assert_eq!(ours[&[1, 0]][z][0], ours[&[0, 0]][z][4]);
+            assert_eq!(ours[&[1, 0]][z][1], 1.0);
+        }
+    }
+
+    #[test]
+    fn test_preview_gray_maps_height_range() {
+        assert_eq!(preview_gray(0.0, 32), 0.5);
+        assert_eq!(preview_gray(32.0, 32), 1.0);
+        assert_eq!(preview_gray(-32.0, 32), 0.0);
+        assert_eq!(preview_gray(16.0, 32), 0.75);
+        // Amplified noise can exceed the range; it saturates instead of wrapping
+        assert_eq!(preview_gray(100.0, 32), 1.0);
+    }
+
+    #[test]
+    fn test_river_bed_follows_curve_and_banks_rise() {
+        // Path descending from y 10 to y 6 along +X, over flat ground at 12
+        let path = [Vector3::new(0.0, 10.0, 0.0), Vector3::new(20.0, 6.0, 0.0)];
+        let (depth, half_width) = (2.0, 4.0);
+        let carve_at = |x: f32, z: f32| {
+            let (distance, y) = closest_on_path_xz(&path, Vector2::new(x, z)).unwrap();
+            river_height(12.0, y - depth, distance, half_width)
+        };
+
+        // Bottom tracks the curve's Y minus depth
+        assert!((carve_at(0.0, 0.0) - 8.0).abs() < 1e-5);
+        assert!((carve_at(10.0, 0.0) - 6.0).abs() < 1e-5);
+        assert!((carve_at(20.0, 0.0) - 4.0).abs() < 1e-5);
+
+        // Banks rise monotonically away from the center line, untouched beyond the width
+        let profile = [0.0, 1.0, 2.0, 3.0, 4.0].map(|z| carve_at(10.0, z));
+        assert!(profile.windows(2).all(|w| w[1] > w[0]), "{profile:?}");
+        assert_eq!(carve_at(10.0, 5.0), 12.0);
+
+        // Ground already below the bed is left alone
+        assert_eq!(river_height(3.0, 6.0, 0.0, half_width), 3.0);
+    }
+
+    #[test]
+    fn test_collision_proxy_grid_scales_and_covers_extent() {
+        let flat = |_: f32, _: f32| Some(1.0);
+        let coarse = proxy_axis(0.0, 64.0, 8.0);
+        let fine = proxy_axis(0.0, 64.0, 4.0);
+        assert_eq!(coarse.len(), 9);
+        assert_eq!(fine.len(), 17);
+        assert_eq!((coarse[0], *coarse.last().unwrap()), (0.0, 64.0));
+        // Uneven extents still end exactly on the edge, never sampling further apart than step
+        let uneven = proxy_axis(-10.0, 15.0, 4.0);
+        assert_eq!((uneven[0], *uneven.last().unwrap()), (-10.0, 15.0));
+        assert!(uneven.windows(2).all(|w| w[1] - w[0] <= 4.0));
+
+        // Halving the spacing quadruples the triangles (8x8 vs 16x16 quads)
+        assert_eq!(grid_triangles(&coarse, &coarse, flat).len(), 8 * 8 * 6);
+        assert_eq!(grid_triangles(&fine, &fine, flat).len(), 16 * 16 * 6);
+
+        // Winding faces up, and quads touching a missing sample are skipped
+        let faces = grid_triangles(&coarse, &coarse, flat);
+        let normal = (faces[2] - faces[0]).cross(faces[1] - faces[0]);
+        assert!(normal.y > 0.0);
+        let holed = grid_triangles(&coarse, &coarse, |x, z| (x > 0.0 || z > 0.0).then_some(1.0));
+        assert_eq!(holed.len(), (8 * 8 - 1) * 6);
+    }
+
+    #[test]
+    fn test_collision_proxy_reaches_the_far_edge_of_the_terrain() {
+        // One loaded chunk spanning 0..64; only it stores the x = 64 / z = 64 edge vertices
+        let dim = Vector3i::new(33, 32, 33);
+        let cell_size = Vector2::new(2.0, 2.0);
+        let height_at = |key: [i32; 2], [x, z]: [i32; 2]| {
+            (key == [0, 0] && (0..dim.x).contains(&x) && (0..dim.z).contains(&z))
+                .then_some(x as f32 + z as f32)
+        };
+        let axis = proxy_axis(0.0, 64.0, 8.0);
+        let faces = grid_triangles(&axis, &axis, |x, z| {
+            proxy_height(x, z, dim, cell_size, height_at)
+        });
+
+        assert_eq!(faces.len(), 8 * 8 * 6);
+        let corner = faces
+            .iter()
+            .find(|p| p.x == 64.0 && p.z == 64.0)
+            .expect("far corner sample");
+        assert_eq!(corner.y, 64.0);
+    }
+
+    #[test]
+    fn test_chunks_above_only_selects_chunks_entirely_above() {
+        let dims = Vector3i::new(33, 32, 33);
+        let cell = Vector2::new(2.0, 2.0);
+        let entry = |key: [i32; 2], min_y: f32, max_y: f32| {
+            let coords = Vector2i::new(key[0], key[1]);
+            (key, chunk_aabb(coords, dims, cell, min_y, max_y))
+        };
+        let bounds = vec![
+            entry([0, 0], 5.0, 12.0),
+            entry([1, 0], -2.0, 3.0),
+            entry([0, 1], 2.0, 20.0),
+        ];
+        assert_eq!(chunks_above(&bounds, 4.0), vec![[0, 0]]);
+        assert_eq!(chunks_above(&bounds, 1.0), vec![[0, 0], [0, 1]]);
+        assert!(chunks_above(&bounds, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_remap_color_pairs_moves_only_the_mapped_slot() {
+        let (one_0, one_1) = texture_index_to_colors(1);
+        let (five_0, five_1) = texture_index_to_colors(5);
+        let mut c0 = vec![one_0, five_0, one_0];
+        let mut c1 = vec![one_1, five_1, one_1];
+        let mut table: [u8; 16] = std::array::from_fn(|i| i as u8);
+        table[1] = 3;
+
+        assert!(remap_color_pairs(&mut c0, &mut c1, &table));
+        let slots: Vec<u8> = c0
+            .iter()
+            .zip(&c1)
+            .map(|(&a, &b)| TextureIndex::from_color_pair(a, b).0)
+            .collect();
+        assert_eq!(slots, vec![3, 5, 3]);
+
+        // Nothing left on slot 1, so a second pass is a no-op
+        assert!(!remap_color_pairs(&mut c0, &mut c1, &table));
+    }
+
+    #[test]
+    fn test_lod_for_distance_counts_reached_thresholds() {
+        let thresholds = [50.0, 100.0, 200.0];
+        assert_eq!(lod_for_distance(0.0, &thresholds), 0);
+        assert_eq!(lod_for_distance(49.9, &thresholds), 0);
+        assert_eq!(lod_for_distance(50.0, &thresholds), 1);
+        assert_eq!(lod_for_distance(150.0, &thresholds), 2);
+        assert_eq!(lod_for_distance(1000.0, &thresholds), 3);
+        assert_eq!(lod_for_distance(1000.0, &[]), 0);
+    }
+
+    #[test]
+    fn test_camera_moved_needs_a_cell_of_travel() {
+        let last = Some(Vector3::new(10.0, 5.0, 10.0));
+        assert!(camera_moved(None, Vector3::ZERO, 2.0));
+        assert!(!camera_moved(last, Vector3::new(10.0, 5.0, 10.0), 2.0));
+        assert!(!camera_moved(last, Vector3::new(11.5, 5.0, 10.0), 2.0));
+        assert!(camera_moved(last, Vector3::new(10.0, 5.0, 12.0), 2.0));
+    }
+
+    #[test]
+    fn test_bake_layout_has_one_child_per_chunk_at_its_position() {
+        let dim = Vector3i::new(33, 32, 33);
+        let cell_size = Vector2::new(2.0, 2.0);
+        let chunks: HashMap<[i32; 2], ()> = [[1, 0], [0, 0], [-1, 2]]
+            .into_iter()
+            .map(|k| (k, ()))
+            .collect();
+
+        let layout = bake_layout(&chunks, dim, cell_size);
+        assert_eq!(layout.len(), chunks.len());
+        let names: HashSet<&str> = layout.iter().map(|(_, n, _)| n.as_str()).collect();
+        assert_eq!(names.len(), chunks.len());
+        assert_eq!(
+            layout[0],
+            (
+                [-1, 2],
+                "Chunk (-1, 2)".to_string(),
+                Vector3::new(-64.0, 0.0, 128.0)
+            )
+        );
+        for (key, _, position) in &layout {
+            let aabb = chunk_aabb(Vector2i::new(key[0], key[1]), dim, cell_size, 0.0, 1.0);
+            assert_eq!(*position, aabb.position);
+        }
+    }
+}